@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use clap::{Args, ValueEnum};
+use zefiro_cwl::{graph::dag::WorkflowGraph, CwlSchema};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Mermaid,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct GraphArgs {
+    /// Path to CWL Workflow document
+    pub schema: String,
+
+    /// Output format of the graph
+    #[arg(long, value_enum, default_value_t)]
+    pub format: GraphFormat,
+}
+
+pub fn run(args: &GraphArgs) -> Result<String> {
+    let CwlSchema::Workflow(workflow) = CwlSchema::from_path(&args.schema)? else {
+        bail!(
+            "Graph can be built only for a Workflow document: {}",
+            args.schema
+        );
+    };
+    let graph = WorkflowGraph::from_workflow(&workflow);
+
+    Ok(match args.format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Mermaid => graph.to_mermaid(),
+        GraphFormat::Json => serde_json::to_string_pretty(&graph)? + "\n",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_data;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(GraphFormat::Dot, "digraph workflow {")]
+    #[case(GraphFormat::Mermaid, "flowchart LR")]
+    #[case(GraphFormat::Json, "{")]
+    fn test_graph_formats(#[case] format: GraphFormat, #[case] expected_prefix: &str) {
+        let output = run(&GraphArgs {
+            schema: test_data("cwl/wf-step-schema.yml"),
+            format,
+        })
+        .unwrap();
+        assert!(output.starts_with(expected_prefix));
+    }
+
+    #[test]
+    fn test_graph_requires_workflow() {
+        let result = run(&GraphArgs {
+            schema: test_data("cwl/clt-step-schema.yml"),
+            format: GraphFormat::Dot,
+        });
+        assert!(result.is_err());
+    }
+}
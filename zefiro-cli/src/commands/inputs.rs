@@ -0,0 +1,142 @@
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use serde_json::Value;
+use zefiro_cwl::schema::types::{Any, CwlSchemaType};
+use zefiro_cwl::CwlSchema;
+
+#[derive(Debug, Args)]
+pub struct InputsArgs {
+    /// Path to CWL schema document
+    pub schema: String,
+
+    /// Print inputs as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Represents an input parameter of `CommandLineTool` or `Workflow`.
+#[derive(Debug, Serialize)]
+pub struct InputParameter {
+    pub id: String,
+    pub r#type: String,
+    pub optional: bool,
+    pub default: Option<Value>,
+    /// The input `File` may be read as a stream (FIFO) instead of being staged whole.
+    pub streamable: Option<bool>,
+}
+
+/// Collects input parameters of the `schema` in the declaration order.
+pub fn schema_inputs(schema: &CwlSchema) -> Result<Vec<InputParameter>> {
+    match schema {
+        CwlSchema::CommandLineTool(clt) => clt
+            .inputs()
+            .map(|(id, input)| {
+                input_parameter(id, &input.r#type, input.default.as_ref(), input.streamable)
+            })
+            .collect(),
+        CwlSchema::Workflow(wf) => wf
+            .inputs()
+            .map(|(id, input)| {
+                input_parameter(id, &input.r#type, input.default.as_ref(), input.streamable)
+            })
+            .collect(),
+        CwlSchema::Operation(op) => op
+            .inputs()
+            .map(|(id, input)| {
+                input_parameter(id, &input.r#type, input.default.as_ref(), input.streamable)
+            })
+            .collect(),
+    }
+}
+
+fn input_parameter(
+    id: &str,
+    r#type: &CwlSchemaType,
+    default: Option<&Any>,
+    streamable: Option<bool>,
+) -> Result<InputParameter> {
+    Ok(InputParameter {
+        id: id.to_string(),
+        r#type: r#type.to_string(),
+        optional: r#type.is_optional(),
+        default: default.map(serde_json::to_value).transpose()?,
+        streamable,
+    })
+}
+
+pub fn run(args: &InputsArgs) -> Result<String> {
+    let schema = CwlSchema::from_path(&args.schema)?;
+    let inputs = schema_inputs(&schema)?;
+    if args.json {
+        return Ok(serde_json::to_string_pretty(&inputs)? + "\n");
+    }
+
+    let rows: Vec<[String; 3]> = inputs
+        .into_iter()
+        .map(|input| {
+            let default = input.default.map(|v| v.to_string()).unwrap_or_default();
+            [input.id, input.r#type, default]
+        })
+        .collect();
+    let header = ["ID".to_string(), "TYPE".to_string(), "DEFAULT".to_string()];
+    let id_width = rows
+        .iter()
+        .chain([&header])
+        .map(|r| r[0].len())
+        .max()
+        .unwrap_or(0);
+    let type_width = rows
+        .iter()
+        .chain([&header])
+        .map(|r| r[1].len())
+        .max()
+        .unwrap_or(0);
+
+    Ok([header]
+        .iter()
+        .chain(rows.iter())
+        .map(|[id, r#type, default]| {
+            format!("{id:id_width$}  {type:type_width$}  {default}", type = r#type)
+                .trim_end()
+                .to_string()
+                + "\n"
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_data;
+
+    #[test]
+    fn test_inputs_table() {
+        let output = run(&InputsArgs {
+            schema: test_data("cwl/clt-step-schema.yml"),
+            json: false,
+        })
+        .unwrap();
+        assert_eq!(
+            output,
+            "ID                      TYPE    DEFAULT\n\
+             in_file                 File\n\
+             out_file                string  \"output.txt\"\n\
+             output_location_subdir  string  \"output/\"\n"
+        );
+    }
+
+    #[test]
+    fn test_inputs_json() {
+        let output = run(&InputsArgs {
+            schema: test_data("cwl/wf-step-schema.yml"),
+            json: true,
+        })
+        .unwrap();
+        let inputs: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(inputs[0]["id"], "step__in_file");
+        assert_eq!(inputs[0]["type"], "File[]");
+        assert_eq!(inputs[1]["optional"], false);
+        assert_eq!(inputs[0]["streamable"], Value::Null);
+    }
+}
@@ -0,0 +1,70 @@
+pub mod graph;
+pub mod inputs;
+pub mod pack;
+pub mod render;
+pub mod validate;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+/// Command-line interface to work with CWL documents.
+#[derive(Debug, Parser)]
+#[command(name = "zefiro", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Validates CWL schema document and, optionally, CWL values against it
+    Validate(validate::ValidateArgs),
+    /// Prints the data flow graph of a CWL Workflow
+    Graph(graph::GraphArgs),
+    /// Writes CWL schema document as a single self-contained file
+    Pack(pack::PackArgs),
+    /// Renders a template into CWL values or schema document
+    Render(render::RenderArgs),
+    /// Prints the input parameters of a CWL schema document
+    Inputs(inputs::InputsArgs),
+}
+
+/// Represents result of a subcommand: text to print, diagnostics to report on stderr and
+/// whether it succeeded.
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub output: String,
+    pub diagnostics: Vec<String>,
+    pub success: bool,
+}
+
+impl CommandOutput {
+    fn success(output: String) -> Self {
+        Self {
+            output,
+            diagnostics: vec![],
+            success: true,
+        }
+    }
+}
+
+impl Command {
+    pub fn run(&self) -> Result<CommandOutput> {
+        match self {
+            Self::Validate(args) => validate::run(args),
+            Self::Graph(args) => graph::run(args).map(CommandOutput::success),
+            Self::Pack(args) => pack::run(args).map(CommandOutput::success),
+            Self::Render(args) => render::run(args).map(CommandOutput::success),
+            Self::Inputs(args) => inputs::run(args).map(CommandOutput::success),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_data(path: &str) -> String {
+    format!(
+        "{}/../zefiro-core/zefiro-cwl/test_data/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        path
+    )
+}
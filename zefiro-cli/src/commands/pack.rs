@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use std::{fs::File, io::BufWriter};
+use zefiro_cwl::CwlSchema;
+
+#[derive(Debug, Args)]
+pub struct PackArgs {
+    /// Path to CWL schema document
+    pub schema: String,
+
+    /// Path to the output file, prints to stdout if not set
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+pub fn run(args: &PackArgs) -> Result<String> {
+    let schema = CwlSchema::from_path(&args.schema)?;
+    match &args.output {
+        Some(path) => {
+            let file =
+                File::create(path).with_context(|| format!("Failed to create file '{path}'"))?;
+            schema.to_yaml(BufWriter::new(file))?;
+            Ok(String::new())
+        }
+        None => Ok(schema.to_string()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_data;
+
+    #[test]
+    fn test_pack_to_file() {
+        let output = tempfile::NamedTempFile::new().unwrap();
+        let output_path = output.path().to_str().unwrap().to_string();
+        run(&PackArgs {
+            schema: test_data("cwl/wf-step-schema.yml"),
+            output: Some(output_path.clone()),
+        })
+        .unwrap();
+
+        let packed = CwlSchema::from_path(&output_path).unwrap();
+        assert!(matches!(packed, CwlSchema::Workflow(_)));
+    }
+
+    #[test]
+    fn test_pack_to_stdout() {
+        let output = run(&PackArgs {
+            schema: test_data("cwl/clt-step-schema.yml"),
+            output: None,
+        })
+        .unwrap();
+        assert!(output.contains("class: CommandLineTool"));
+    }
+}
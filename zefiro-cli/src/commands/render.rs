@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use serde_json::Value;
+use std::fs;
+use zefiro_cwl::{CwlSchema, CwlValues, TemplateRender};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum RenderTarget {
+    Values,
+    Schema,
+}
+
+#[derive(Debug, Args)]
+pub struct RenderArgs {
+    /// Path to Tera template
+    #[arg(long)]
+    pub template: String,
+
+    /// Path to JSON file with template content
+    #[arg(long)]
+    pub content: String,
+
+    /// Check that rendered document is a valid CWL values or schema document
+    #[arg(long, value_enum)]
+    pub to: Option<RenderTarget>,
+}
+
+pub fn run(args: &RenderArgs) -> Result<String> {
+    let template = fs::read_to_string(&args.template)
+        .with_context(|| format!("Failed to read template '{}'", args.template))?;
+    let content: Value = serde_json::from_str(
+        &fs::read_to_string(&args.content)
+            .with_context(|| format!("Failed to read content '{}'", args.content))?,
+    )
+    .with_context(|| format!("Failed to parse content '{}'", args.content))?;
+
+    let rendered = TemplateRender::new(content, &template)?.render()?;
+    match args.to {
+        Some(RenderTarget::Values) => {
+            CwlValues::from_string(&rendered)?;
+        }
+        Some(RenderTarget::Schema) => {
+            rendered.parse::<CwlSchema>()?;
+        }
+        None => {}
+    }
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_tmp(content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{content}").unwrap();
+        file
+    }
+
+    #[test]
+    fn test_render_values() {
+        let template = write_tmp("in_file:\n  class: File\n  location: {{ location }}/in.txt\n");
+        let content = write_tmp(r#"{"location": "s3://bucket"}"#);
+        let output = run(&RenderArgs {
+            template: template.path().to_str().unwrap().to_string(),
+            content: content.path().to_str().unwrap().to_string(),
+            to: Some(RenderTarget::Values),
+        })
+        .unwrap();
+        assert_eq!(
+            output,
+            "in_file:\n  class: File\n  location: s3://bucket/in.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_render_invalid_schema() {
+        let template = write_tmp("class: {{ class }}\n");
+        let content = write_tmp(r#"{"class": "CommandLineTool"}"#);
+        let result = run(&RenderArgs {
+            template: template.path().to_str().unwrap().to_string(),
+            content: content.path().to_str().unwrap().to_string(),
+            to: Some(RenderTarget::Schema),
+        });
+        assert!(result.is_err());
+    }
+}
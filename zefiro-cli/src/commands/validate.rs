@@ -0,0 +1,367 @@
+use crate::commands::{inputs::schema_inputs, CommandOutput};
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+use zefiro_cwl::error::ValidationError;
+use zefiro_cwl::lint::linter::{LintConfig, LintFinding, LintRule, Severity};
+use zefiro_cwl::{CwlSchema, CwlValues};
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// Path to CWL schema document
+    pub schema: String,
+
+    /// Path to CWL values document to validate against the schema
+    #[arg(long)]
+    pub values: Option<String>,
+
+    /// Treat values that are not declared as schema inputs as errors
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Print validation result as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Report findings of the lint rule as errors
+    #[arg(long, value_name = "RULE")]
+    pub deny: Vec<LintRule>,
+
+    /// Turn off the lint rule
+    #[arg(long, value_name = "RULE")]
+    pub allow: Vec<LintRule>,
+}
+
+impl ValidateArgs {
+    fn lint_config(&self) -> LintConfig {
+        let config = self
+            .deny
+            .iter()
+            .fold(LintConfig::default(), |config, rule| {
+                config.severity(*rule, Severity::Error)
+            });
+        self.allow
+            .iter()
+            .fold(config, |config, rule| config.disable(*rule))
+    }
+}
+
+/// Problems found in the schema and values documents, split by severity.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    fn error(&mut self, error: &anyhow::Error) {
+        self.errors.push(format!("{error:#}"));
+    }
+
+    fn extend(&mut self, problems: impl IntoIterator<Item = ValidationError>) {
+        for problem in problems {
+            // Severity is conveyed by the list the problem is reported in
+            let message = format!("{}: {}", problem.path, problem.message);
+            if problem.severity == Severity::Error {
+                self.errors.push(message);
+            } else {
+                self.warnings.push(message);
+            }
+        }
+    }
+}
+
+/// Collects all validation problems of the `schema` and values documents.
+pub fn validation_report(args: &ValidateArgs, schema: &CwlSchema) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    report.extend(schema.problems());
+    let Some(values_path) = &args.values else {
+        return report;
+    };
+    let values = match CwlValues::from_path(values_path) {
+        Ok(values) => values,
+        Err(e) => {
+            report.error(&e.into());
+            return report;
+        }
+    };
+    let inputs = match schema_inputs(schema) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            report.error(&e);
+            return report;
+        }
+    };
+
+    report.errors.extend(
+        inputs
+            .iter()
+            .filter(|input| {
+                !input.optional && input.default.is_none() && !values.contains_key(&input.id)
+            })
+            .map(|input| format!("Missing value for required input '{}'", input.id)),
+    );
+    if let Err(problems) = values.validate(schema) {
+        report.extend(problems);
+    }
+    if args.strict {
+        let mut unknown: Vec<&String> = values
+            .keys()
+            .filter(|key| !inputs.iter().any(|input| &input.id == *key))
+            .collect();
+        unknown.sort();
+        report.errors.extend(
+            unknown
+                .into_iter()
+                .map(|key| format!("Value '{key}' does not match any schema input")),
+        );
+    }
+
+    report
+}
+
+/// Lints the `schema` with the rules selected by `args`.
+pub fn lint_findings(args: &ValidateArgs, schema: &CwlSchema) -> Vec<LintFinding> {
+    schema.lint(&args.lint_config())
+}
+
+pub fn run(args: &ValidateArgs) -> Result<CommandOutput> {
+    let (report, findings) = match CwlSchema::from_path(&args.schema) {
+        Ok(schema) => (
+            validation_report(args, &schema),
+            lint_findings(args, &schema),
+        ),
+        Err(e) => {
+            let mut report = ValidationReport::default();
+            report.error(&e.into());
+            (report, vec![])
+        }
+    };
+    let ValidationReport { errors, warnings } = report;
+    let success = errors.is_empty() && findings.iter().all(|f| f.severity != Severity::Error);
+    if args.json {
+        let output = serde_json::to_string_pretty(&json!({
+            "valid": success,
+            "errors": errors,
+            "warnings": warnings,
+            "findings": findings,
+        }))? + "\n";
+        return Ok(CommandOutput {
+            output,
+            diagnostics: vec![],
+            success,
+        });
+    }
+    // Errors are printed on stdout, warnings and other findings are reported on stderr
+    let mut output: String = errors.iter().map(|e| format!("error: {e}\n")).collect();
+    let mut diagnostics: Vec<String> = warnings.iter().map(|w| format!("warning: {w}")).collect();
+    for finding in &findings {
+        if finding.severity == Severity::Error {
+            output += &format!("{finding}\n");
+        } else {
+            diagnostics.push(finding.to_string());
+        }
+    }
+    if success {
+        output += &format!("{} is valid\n", args.schema);
+    }
+
+    Ok(CommandOutput {
+        output,
+        diagnostics,
+        success,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_data;
+    use rstest::rstest;
+    use std::io::Write;
+
+    fn report(args: &ValidateArgs) -> ValidationReport {
+        validation_report(args, &CwlSchema::from_path(&args.schema).unwrap())
+    }
+
+    fn args(schema: &str, values: Option<&str>, strict: bool) -> ValidateArgs {
+        ValidateArgs {
+            schema: schema.to_string(),
+            values: values.map(String::from),
+            strict,
+            json: false,
+            deny: vec![],
+            allow: vec![LintRule::NonKebabCaseId],
+        }
+    }
+
+    #[rstest]
+    #[case("cwl/clt-step-schema.yml")]
+    #[case("cwl/wf-step-schema.yml")]
+    fn test_validate_schema(#[case] schema: &str) {
+        let result = run(&args(&test_data(schema), None, true)).unwrap();
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_validate_values() {
+        let result = run(&args(
+            &test_data("cwl/clt-step-schema.yml"),
+            Some(&test_data("cwl/clt-step-values.yml")),
+            true,
+        ))
+        .unwrap();
+        assert!(result.success, "{}", result.output);
+    }
+
+    #[test]
+    fn test_validate_missing_and_unknown_values() {
+        let mut values = tempfile::NamedTempFile::new().unwrap();
+        writeln!(values, "out_file: output.txt\nunknown: 1").unwrap();
+        let values_path = values.path().to_str().unwrap();
+        let schema = test_data("cwl/clt-step-schema.yml");
+
+        let report = report(&args(&schema, Some(values_path), false));
+        assert_eq!(
+            report.errors,
+            vec!["Missing value for required input 'in_file'"]
+        );
+
+        let result = run(&args(&schema, Some(values_path), true)).unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.output,
+            "error: Missing value for required input 'in_file'\n\
+             error: Value 'unknown' does not match any schema input\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_duplicate_ids() {
+        let mut schema = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            schema,
+            "cwlVersion: v1.2\nclass: CommandLineTool\ninputs:\n  - id: a\n    type: string\n\
+             \x20 - id: a\n    type: int\noutputs: []"
+        )
+        .unwrap();
+        let report = report(&args(schema.path().to_str().unwrap(), None, false));
+        assert_eq!(report.errors, vec!["inputs[1].id: Duplicate id 'a'"]);
+    }
+
+    #[test]
+    fn test_validate_reports_errors_and_warnings() {
+        let report = report(&args(&test_data("cwl/wf-invalid-schema.yml"), None, false));
+        assert_eq!(
+            report,
+            ValidationReport {
+                errors: vec![
+                    "inputs[1].id: Duplicate id 'reads'".to_string(),
+                    "steps[0].in[1].valueFrom: JavaScript expression requires \
+                     InlineJavascriptRequirement"
+                        .to_string(),
+                    "steps[0].run.requirements[0]: DockerRequirement requires one of dockerPull, \
+                     dockerLoad, dockerFile, dockerImport or dockerImageId"
+                        .to_string(),
+                    "steps[0].run.outputs[0].outputBinding.glob: JavaScript expression requires \
+                     InlineJavascriptRequirement"
+                        .to_string(),
+                    "steps[0].in[0].source: Unknown source 'sample'".to_string(),
+                    "steps[0].scatter: Unknown scatter input 'read'".to_string(),
+                    "outputs[0].outputSource: Unknown source 'align/report'".to_string(),
+                    "inputs[2].default: Expected int, got string".to_string(),
+                ],
+                warnings: vec![
+                    "steps[0].in[2]: Input 'extra' is not declared by the run process".to_string()
+                ],
+            }
+        );
+
+        let result = run(&args(&test_data("cwl/wf-invalid-schema.yml"), None, false)).unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.diagnostics[0],
+            "warning: steps[0].in[2]: Input 'extra' is not declared by the run process"
+        );
+        assert!(!result.output.contains("not declared"));
+    }
+
+    #[test]
+    fn test_validate_schema_and_values_errors() {
+        let mut schema = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            schema,
+            "cwlVersion: v1.2\nclass: CommandLineTool\ninputs:\n  - id: a\n    type: string\n\
+             \x20 - id: n\n    type: int\n    default: x\noutputs: []"
+        )
+        .unwrap();
+        let mut values = tempfile::NamedTempFile::new().unwrap();
+        writeln!(values, "b: 1").unwrap();
+
+        let report = report(&args(
+            schema.path().to_str().unwrap(),
+            Some(values.path().to_str().unwrap()),
+            true,
+        ));
+        assert_eq!(
+            report.errors,
+            vec![
+                "inputs[1].default: Expected int, got string",
+                "Missing value for required input 'a'",
+                "Value 'b' does not match any schema input",
+            ]
+        );
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validate_invalid_schema_json() {
+        let mut schema = tempfile::NamedTempFile::new().unwrap();
+        writeln!(schema, "cwlVersion: draft-3\nclass: CommandLineTool").unwrap();
+        let mut args = args(schema.path().to_str().unwrap(), None, false);
+        args.json = true;
+
+        let result = run(&args).unwrap();
+        assert!(!result.success);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "errors": ["Unsupported CWL version: draft-3"],
+                "warnings": [],
+                "findings": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_lint_findings() {
+        let mut schema = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            schema,
+            "cwlVersion: v1.2\nclass: CommandLineTool\nid: tool\ninputs: []\noutputs: []\n\
+             requirements:\n  - class: DockerRequirement\n    dockerPull: tool:latest\n\
+             \x20 - class: ResourceRequirement\n  - class: ToolTimeLimit\n    timelimit: 60"
+        )
+        .unwrap();
+        let mut args = args(schema.path().to_str().unwrap(), None, false);
+
+        let result = run(&args).unwrap();
+        assert!(result.success);
+        assert!(result.diagnostics[0]
+            .starts_with("warning[unpinned-image]: requirements: Docker image 'tool:latest'"));
+        assert!(result.diagnostics[0].ends_with(')'));
+        assert!(result.diagnostics[0].contains(" (help: "));
+
+        args.deny = vec![LintRule::UnpinnedImage];
+        let result = run(&args).unwrap();
+        assert!(!result.success);
+        assert!(result.output.starts_with("error[unpinned-image]"));
+        assert!(result.output.contains(" (help: "));
+
+        args.allow.push(LintRule::UnpinnedImage);
+        let result = run(&args).unwrap();
+        assert!(result.success, "{}", result.output);
+    }
+}
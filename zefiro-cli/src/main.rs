@@ -1,3 +1,60 @@
-fn main() {
-    println!("Hello, world!");
+mod commands;
+
+use clap::Parser;
+use commands::Cli;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command.run() {
+        Ok(result) => {
+            for diagnostic in &result.diagnostics {
+                eprintln!("{diagnostic}");
+            }
+            print!("{}", result.output);
+            if result.success {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", error_message(&e));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Joins messages of the error chain, skipping causes that are already part of the message,
+/// e.g. `CwlError` messages include their source.
+fn error_message(error: &anyhow::Error) -> String {
+    let mut message = String::new();
+    for cause in error.chain().map(ToString::to_string) {
+        if message.ends_with(&cause) {
+            continue;
+        }
+        if !message.is_empty() {
+            message += ": ";
+        }
+        message += &cause;
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use zefiro_cwl::CwlSchema;
+
+    #[test]
+    fn test_error_message() {
+        let error = CwlSchema::from_path("missing.yml")
+            .context("Failed to pack")
+            .unwrap_err();
+        assert_eq!(
+            error_message(&error),
+            "Failed to pack: Failed to open file 'missing.yml': No such file or directory (os error 2)"
+        );
+    }
 }
@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zefiro_cwl::schema::command_line_tool::CommandLineTool;
+use zefiro_cwl::CwlSchema;
+
+const INPUTS: usize = 200;
+
+fn tool() -> CommandLineTool {
+    let inputs: String = (0..INPUTS)
+        .map(|i| format!("  - id: input_{i}\n    type: string\n"))
+        .collect();
+    let yaml = format!("cwlVersion: v1.2\nclass: CommandLineTool\nid: tool\ninputs:\n{inputs}outputs: []\nrequirements: []\n");
+    match yaml.parse::<CwlSchema>().unwrap() {
+        CwlSchema::CommandLineTool(tool) => tool,
+        _ => unreachable!(),
+    }
+}
+
+fn lookup(c: &mut Criterion) {
+    let tool = tool();
+    let ids: Vec<String> = (0..INPUTS).map(|i| format!("input_{i}")).collect();
+
+    let mut group = c.benchmark_group("lookup_200_inputs");
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| {
+            for id in &ids {
+                black_box(tool.inputs.iter().find(|input| &input.id == id));
+            }
+        })
+    });
+    group.bench_function("input_by_id", |b| {
+        b.iter(|| {
+            for id in &ids {
+                black_box(tool.input(id));
+            }
+        })
+    });
+    group.finish();
+
+    let missing: Vec<String> = (0..INPUTS).map(|i| format!("missing_{i}")).collect();
+    let mut group = c.benchmark_group("lookup_200_inputs_miss");
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| {
+            for id in &missing {
+                black_box(tool.inputs.iter().find(|input| &input.id == id));
+            }
+        })
+    });
+    group.bench_function("input_by_id", |b| {
+        b.iter(|| {
+            for id in &missing {
+                black_box(tool.input(id));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
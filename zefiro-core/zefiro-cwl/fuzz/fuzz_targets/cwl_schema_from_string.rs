@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zefiro_cwl::CwlSchema;
+
+fuzz_target!(|data: &str| {
+    if let Ok(schema) = CwlSchema::from_string(data) {
+        let _ = schema.to_string();
+        let _ = schema.validate();
+    }
+});
@@ -0,0 +1,51 @@
+//! Helpers to implement `arbitrary::Arbitrary` for recursive CWL types.
+use crate::schema::types::Extensions;
+use arbitrary::{Result, Unstructured};
+use serde_yaml::Value;
+
+/// Maximum nesting level of recursive types, e.g. arrays of arrays in `type`.
+pub(crate) const MAX_DEPTH: usize = 4;
+
+/// Maximum number of elements in arrays and maps of recursive types.
+const MAX_LEN: usize = 4;
+
+/// Generates a bounded vector of elements produced by `element`.
+pub(crate) fn arbitrary_vec<'a, T>(
+    u: &mut Unstructured<'a>,
+    mut element: impl FnMut(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    let len = u.int_in_range(0..=MAX_LEN)?;
+    (0..len).map(|_| element(u)).collect()
+}
+
+/// Generates extension fields with namespaced keys that never match the known fields.
+pub(crate) fn arbitrary_extensions(u: &mut Unstructured<'_>) -> Result<Extensions> {
+    arbitrary_vec(u, |u| {
+        let key = format!("ext:{}", u.arbitrary::<String>()?);
+        Ok((key, Value::String(u.arbitrary()?)))
+    })
+    .map(|fields| fields.into_iter().collect())
+}
+
+/// Runs `check` over `cases` values of `T` generated from deterministic pseudo-random bytes.
+#[cfg(test)]
+pub(crate) fn check_arbitrary<T>(cases: usize, mut check: impl FnMut(T))
+where
+    T: for<'a> arbitrary::Arbitrary<'a>,
+{
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..cases {
+        let bytes: Vec<u8> = (0..1024)
+            .map(|_| {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        if let Ok(value) = T::arbitrary(&mut Unstructured::new(&bytes)) {
+            check(value);
+        }
+    }
+}
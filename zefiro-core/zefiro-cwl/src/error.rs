@@ -0,0 +1,292 @@
+use serde::Serialize;
+use std::fmt;
+use thiserror::Error;
+
+/// Represents errors that can occur while reading, writing and evaluating CWL documents.
+#[derive(Debug, Error)]
+pub enum CwlError {
+    /// File can't be opened or created.
+    #[error("Failed to open file '{path}': {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Document is not a valid YAML or doesn't match the CWL object model.
+    #[error("{}", YamlMessage(.context, .source))]
+    YamlSyntax {
+        /// What was being read, e.g. `Failed to deserialize CWL values from string`.
+        context: Option<String>,
+        location: Option<Location>,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    /// Document can't be serialized into YAML.
+    #[error("Failed to serialize {document}: {source}")]
+    Serialization {
+        document: &'static str,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    /// Document doesn't declare `cwlVersion`.
+    #[error("Failed to determine CWL specification version.")]
+    MissingVersion,
+
+    /// Document declares `cwlVersion` that is not supported.
+    #[error("Unsupported CWL version: {found}")]
+    UnsupportedVersion {
+        found: String,
+        supported: &'static [&'static str],
+    },
+
+    /// Document doesn't declare `class`.
+    #[error("Failed to determine CWL document class.")]
+    MissingClass,
+
+    /// Document declares `class` that is not supported.
+    #[error("Unsupported CWL document class: {0}")]
+    UnknownClass(String),
+
+    /// `$import` directives or step `run` paths of the documents refer to each other,
+    /// paths are listed in the loading order.
+    #[error("Circular reference between documents: {}", .0.join(" -> "))]
+    CircularReference(Vec<String>),
+
+    /// Process referenced by `run` of a workflow step can't be loaded.
+    #[error("Failed to load '{path}' run by step '{step}': {source}")]
+    StepRun {
+        step: String,
+        path: String,
+        #[source]
+        source: Box<CwlError>,
+    },
+
+    /// Document is syntactically correct, but semantically invalid.
+    #[error("{}", ValidationErrors(.0))]
+    Validation(Vec<ValidationError>),
+
+    /// Document uses features that can't be expressed in the target format.
+    #[error("{}", UnsupportedFeatures(.0))]
+    Unsupported(Vec<UnsupportedFeature>),
+
+    /// JavaScript expression can't be evaluated.
+    #[error(transparent)]
+    Expression(#[from] JsError),
+
+    /// Template can't be rendered into a document.
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+}
+
+/// Error of the JavaScript engine, e.g. `deno_core` or `serde_v8` error.
+pub type EngineError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Represents errors of `JsExecutor`.
+#[derive(Debug, Error)]
+pub enum JsError {
+    #[error("Failed to initialize JavaScript context: {0}")]
+    Init(#[source] EngineError),
+
+    #[error("Failed to execute JavaScript expression: {0}")]
+    Execute(#[source] EngineError),
+
+    #[error("Failed to deserialize result of JavaScript expression: {0}")]
+    Deserialize(#[source] EngineError),
+}
+
+/// Represents errors of `TemplateRender`.
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Failed to parse template: {0}")]
+    Parse(#[source] EngineError),
+
+    #[error("Content must be a JSON object, got: {0}")]
+    Content(serde_json::Value),
+
+    #[error("Failed to render template: {0}")]
+    Render(#[source] EngineError),
+}
+
+impl CwlError {
+    /// Creates `CwlError::YamlSyntax` keeping the position of the error in the document.
+    pub fn yaml(source: serde_yaml::Error) -> Self {
+        Self::YamlSyntax {
+            context: None,
+            location: Location::of(&source),
+            source,
+        }
+    }
+
+    /// Returns `CwlError::Validation` with the `errors` if there are any.
+    pub(crate) fn check(errors: Vec<ValidationError>) -> Result<(), Self> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Self::Validation(errors))
+        }
+    }
+
+    /// Creates `CwlError::YamlSyntax` whose message starts with the `context`.
+    pub fn yaml_with_context(context: impl Into<String>, source: serde_yaml::Error) -> Self {
+        Self::YamlSyntax {
+            context: Some(context.into()),
+            location: Location::of(&source),
+            source,
+        }
+    }
+}
+
+/// Position in a YAML document, both `line` and `column` start from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn of(error: &serde_yaml::Error) -> Option<Self> {
+        error.location().map(|location| Self {
+            line: location.line(),
+            column: location.column(),
+        })
+    }
+}
+
+/// Severity of a `ValidationError` or a `LintFinding`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// Describes a single semantic error in a CWL document, e.g. invalid `source` reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Problems with `Severity::Error` make the document invalid, warnings point to
+    /// likely mistakes.
+    pub severity: Severity,
+    /// Path to the invalid field, e.g. `steps[0].in[1].source`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "{}: {}", self.path, self.message),
+            severity => write!(f, "{}: {severity}: {}", self.path, self.message),
+        }
+    }
+}
+
+struct YamlMessage<'a>(&'a Option<String>, &'a serde_yaml::Error);
+
+impl fmt::Display for YamlMessage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(context) => write!(f, "{context}: {}", self.1),
+            None => write!(f, "{}", self.1),
+        }
+    }
+}
+
+struct ValidationErrors<'a>(&'a [ValidationError]);
+
+impl fmt::Display for ValidationErrors<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CWL document is invalid:")?;
+        for error in self.0 {
+            write!(f, "\n  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Describes a feature of a workflow step that can't be converted, e.g. JavaScript `outputEval`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedFeature {
+    pub step: String,
+    pub feature: String,
+}
+
+impl fmt::Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step '{}': {}", self.step, self.feature)
+    }
+}
+
+struct UnsupportedFeatures<'a>(&'a [UnsupportedFeature]);
+
+impl fmt::Display for UnsupportedFeatures<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CWL document uses unsupported features:")?;
+        for feature in self.0 {
+            write!(f, "\n  - {feature}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_error_message() {
+        let error = CwlError::Validation(vec![
+            ValidationError {
+                severity: Severity::Error,
+                path: "steps[0].in[0].source".to_string(),
+                message: "Unknown source 'missing'".to_string(),
+            },
+            ValidationError {
+                severity: Severity::Error,
+                path: "outputs[0].outputSource".to_string(),
+                message: "Unknown source 'step/missing'".to_string(),
+            },
+            ValidationError {
+                severity: Severity::Warning,
+                path: "steps[0].in[1]".to_string(),
+                message: "Input 'extra' is not declared by the run process".to_string(),
+            },
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "CWL document is invalid:\n  \
+             - steps[0].in[0].source: Unknown source 'missing'\n  \
+             - outputs[0].outputSource: Unknown source 'step/missing'\n  \
+             - steps[0].in[1]: warning: Input 'extra' is not declared by the run process"
+        );
+    }
+
+    #[test]
+    fn test_yaml_error_location() {
+        let source = serde_yaml::from_str::<serde_yaml::Value>("a: [1, 2").unwrap_err();
+        let CwlError::YamlSyntax { location, .. } = CwlError::yaml_with_context("Failed", source)
+        else {
+            panic!("Expected YamlSyntax error");
+        };
+        assert_eq!(location.map(|l| l.line), Some(2));
+    }
+
+    #[test]
+    fn test_cwlerror_into_anyhow() {
+        let error: anyhow::Error = CwlError::MissingClass.into();
+        assert_eq!(error.to_string(), "Failed to determine CWL document class.");
+    }
+}
@@ -0,0 +1,1326 @@
+use crate::error::{CwlError, UnsupportedFeature};
+use crate::schema::command_line_tool::{
+    shell_quote, Argument, CommandInputParameter, CommandLine, CommandLineTool, InputBinding,
+};
+use crate::schema::requirements::{
+    DockerRequirement, ResourceRequirement, Timelimit, ToolTimeLimit,
+};
+use crate::schema::types::{Any, CwlSchemaType, Scatter, Source};
+use crate::schema::workflow::{StepRun, Workflow, WorkflowStep};
+use crate::values::document::CwlValues;
+use crate::values::types::CwlValueType;
+use serde_json::{json, Map, Value as JValue};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+
+const ARGO_API_VERSION: &str = "argoproj.io/v1alpha1";
+const ENTRYPOINT: &str = "main";
+const INPUTS_DIR: &str = "/inputs";
+const WORKING_DIR: &str = "/workdir";
+
+/// Maps location of a CWL `File` or `Directory` into an Argo artifact location.
+pub trait ArtifactLocationMapper {
+    /// Returns artifact location fields, e.g. `{"s3": {"bucket": ..., "key": ...}}`,
+    /// or `None` if the `location` can't be mapped.
+    fn map(&self, location: &str) -> Option<JValue>;
+}
+
+/// Maps `s3://`, `gs://` and `http(s)://` locations into the corresponding Argo artifacts.
+#[derive(Clone, Debug, Default)]
+pub struct DefaultArtifactLocationMapper;
+
+impl ArtifactLocationMapper for DefaultArtifactLocationMapper {
+    fn map(&self, location: &str) -> Option<JValue> {
+        let (scheme, path) = location.split_once("://")?;
+        let kind = match scheme {
+            "s3" => "s3",
+            "gs" => "gcs",
+            "http" | "https" => return Some(json!({ "http": { "url": location } })),
+            _ => return None,
+        };
+        let (bucket, key) = path.split_once('/')?;
+        Some(json!({ kind: { "bucket": bucket, "key": key } }))
+    }
+}
+
+/// Options of the conversion into an Argo Workflow manifest.
+pub struct ArgoOptions {
+    /// Prefix of the Argo Workflow name, `<workflow id>-` or, for a workflow without id,
+    /// `workflow-` if not set.
+    pub generate_name: Option<String>,
+    pub namespace: Option<String>,
+    pub service_account_name: Option<String>,
+    pub artifact_mapper: Box<dyn ArtifactLocationMapper>,
+}
+
+impl Default for ArgoOptions {
+    fn default() -> Self {
+        Self {
+            generate_name: None,
+            namespace: None,
+            service_account_name: None,
+            artifact_mapper: Box::new(DefaultArtifactLocationMapper),
+        }
+    }
+}
+
+/// Converts CWL `workflow` with input `values` into an Argo Workflow manifest.
+///
+/// Every step becomes a container template and a task of the `main` DAG template:
+/// non-`File` inputs are passed as parameters, `File` inputs as artifacts placed under
+/// `/inputs/<id>`, scatter is expressed with `withParam` over a workflow parameter.
+/// Requirements of the workflow apply to the steps that don't declare their own.
+/// Workflow outputs are not exported, they are available as artifacts of the steps.
+///
+/// Returns `CwlError::Validation` if ids of the workflow are missing or not unique,
+/// and `CwlError::Unsupported` listing all features that can't be expressed in Argo,
+/// e.g. JavaScript expressions in `outputEval` or `valueFrom`.
+///
+/// ```
+/// use zefiro_cwl::export::argo::{to_argo_workflow, ArgoOptions};
+/// use zefiro_cwl::{CwlSchema, CwlValues};
+///
+/// let schema = CwlSchema::from_path("test_data/cwl/wf-argo-schema.yml").unwrap();
+/// let values = CwlValues::from_path("test_data/cwl/wf-argo-values.yml").unwrap();
+/// if let CwlSchema::Workflow(workflow) = schema {
+///     let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+///     assert_eq!(manifest["kind"].as_str(), Some("Workflow"));
+/// }
+/// ```
+pub fn to_argo_workflow(
+    workflow: &Workflow,
+    values: &CwlValues,
+    opts: &ArgoOptions,
+) -> Result<Value, CwlError> {
+    CwlError::check(workflow.validate_ids())?;
+    let mut converter = Converter {
+        workflow,
+        values,
+        opts,
+        unsupported: vec![],
+    };
+    let manifest = converter.workflow();
+    if !converter.unsupported.is_empty() {
+        return Err(CwlError::Unsupported(converter.unsupported));
+    }
+
+    serde_yaml::to_value(manifest).map_err(|source| CwlError::Serialization {
+        document: "Argo Workflow",
+        source,
+    })
+}
+
+struct Converter<'a> {
+    workflow: &'a Workflow,
+    values: &'a CwlValues,
+    opts: &'a ArgoOptions,
+    unsupported: Vec<UnsupportedFeature>,
+}
+
+impl Converter<'_> {
+    fn workflow(&mut self) -> JValue {
+        let parameters: Vec<JValue> = self
+            .workflow
+            .inputs()
+            .filter(|(_, input)| !contains_path_type(&input.r#type))
+            .map(|(id, input)| {
+                let value = self
+                    .values
+                    .get(id)
+                    .and_then(|value| serde_json::to_value(value).ok())
+                    .or_else(|| {
+                        input
+                            .default
+                            .as_ref()
+                            .and_then(|d| serde_json::to_value(d).ok())
+                    });
+                match value {
+                    Some(value) => json!({ "name": id, "value": parameter_value(&value) }),
+                    None if input.r#type.is_optional() => json!({ "name": id, "value": "" }),
+                    None => json!({ "name": id }),
+                }
+            })
+            .collect();
+
+        let tasks: Vec<JValue> = self
+            .workflow
+            .steps
+            .iter()
+            .map(|step| self.task(step))
+            .collect();
+        let mut templates = vec![json!({ "name": ENTRYPOINT, "dag": { "tasks": tasks } })];
+        templates.extend(self.workflow.steps.iter().map(|step| self.template(step)));
+
+        let mut metadata = Map::new();
+        let generate_name =
+            self.opts
+                .generate_name
+                .clone()
+                .unwrap_or_else(|| match self.workflow.id.as_str() {
+                    "" => "workflow-".to_string(),
+                    id => format!("{id}-"),
+                });
+        metadata.insert("generateName".to_string(), json!(generate_name));
+        if let Some(namespace) = &self.opts.namespace {
+            metadata.insert("namespace".to_string(), json!(namespace));
+        }
+
+        let mut spec = Map::new();
+        spec.insert("entrypoint".to_string(), json!(ENTRYPOINT));
+        if let Some(service_account_name) = &self.opts.service_account_name {
+            spec.insert(
+                "serviceAccountName".to_string(),
+                json!(service_account_name),
+            );
+        }
+        spec.insert("arguments".to_string(), json!({ "parameters": parameters }));
+        spec.insert("templates".to_string(), JValue::Array(templates));
+
+        json!({
+            "apiVersion": ARGO_API_VERSION,
+            "kind": "Workflow",
+            "metadata": metadata,
+            "spec": spec,
+        })
+    }
+
+    fn task(&mut self, step: &WorkflowStep) -> JValue {
+        let step_id = step.id.clone().unwrap_or_default();
+        let scatter = scatter_inputs(step);
+        if scatter.len() > 1 {
+            self.unsupported(&step_id, "scatter over multiple inputs");
+        }
+        if step.when.is_some() {
+            self.unsupported(&step_id, "when condition");
+        }
+
+        let mut task = Map::new();
+        task.insert("name".to_string(), json!(step_id));
+        task.insert("template".to_string(), json!(step_id));
+        let mut dependencies: Vec<String> = vec![];
+        let mut parameters = vec![];
+        let mut artifacts = vec![];
+
+        for input in &step.r#in {
+            if input.pick_value.is_some() {
+                self.unsupported(&step_id, &format!("pickValue of input '{}'", input.id));
+            }
+            if input.link_merge.is_some() {
+                self.unsupported(&step_id, &format!("linkMerge of input '{}'", input.id));
+            }
+            let Some(tool_input) = step.run.as_tool().and_then(|tool| tool.input(&input.id)) else {
+                continue;
+            };
+            let is_path = is_path_type(&tool_input.r#type);
+            if !is_path && contains_path_type(&tool_input.r#type) {
+                // Reported with the inputs of the template
+                continue;
+            }
+            if input.value_from.is_some() {
+                self.unsupported(&step_id, &format!("valueFrom of input '{}'", input.id));
+                continue;
+            }
+            let source = match &input.source {
+                Some(Source::SingleSource(source)) => source,
+                Some(Source::MultiSources(_)) => {
+                    self.unsupported(
+                        &step_id,
+                        &format!("multiple sources of input '{}'", input.id),
+                    );
+                    continue;
+                }
+                None => {
+                    match (is_path, &input.default) {
+                        (true, Some(_)) => self.unsupported(
+                            &step_id,
+                            &format!("File default of input '{}'", input.id),
+                        ),
+                        (false, Some(default)) => {
+                            let value = serde_json::to_value(default).unwrap_or_default();
+                            parameters.push(
+                                json!({ "name": input.id, "value": parameter_value(&value) }),
+                            );
+                        }
+                        (_, None) => {}
+                    }
+                    continue;
+                }
+            };
+            let source = source.trim_start_matches('#');
+
+            if let Some((upstream_id, output_id)) = source.split_once('/') {
+                if !dependencies.iter().any(|d| d == upstream_id) {
+                    dependencies.push(upstream_id.to_string());
+                }
+                let upstream = self.workflow.step(upstream_id);
+                if upstream.is_some_and(|s| s.scatter.is_some()) {
+                    self.unsupported(
+                        &step_id,
+                        &format!("input '{}' from scattered step '{upstream_id}'", input.id),
+                    );
+                } else if !is_path {
+                    self.unsupported(
+                        &step_id,
+                        &format!("non-File input '{}' from step output '{source}'", input.id),
+                    );
+                } else {
+                    artifacts.push(json!({
+                        "name": input.id,
+                        "from": format!("{{{{tasks.{upstream_id}.outputs.artifacts.{output_id}}}}}"),
+                    }));
+                }
+            } else if scatter.contains(&input.id.as_str()) {
+                if is_path {
+                    self.unsupported(&step_id, &format!("scatter over File input '{}'", input.id));
+                } else {
+                    task.insert(
+                        "withParam".to_string(),
+                        json!(format!("{{{{workflow.parameters.{source}}}}}")),
+                    );
+                    parameters.push(json!({ "name": input.id, "value": "{{item}}" }));
+                }
+            } else if is_path {
+                match self.artifact_location(source) {
+                    Ok(Some(mut artifact)) => {
+                        artifact.insert("name".to_string(), json!(input.id));
+                        artifacts.push(JValue::Object(artifact));
+                    }
+                    // Optional artifact of the template
+                    Ok(None) if tool_input.r#type.is_optional() => {}
+                    Ok(None) => self.unsupported(
+                        &step_id,
+                        &format!("missing location of File input '{source}'"),
+                    ),
+                    Err(feature) => self.unsupported(&step_id, &feature),
+                }
+            } else if let StaticValue::Absent = self.static_value(step, tool_input) {
+                // Optional parameter of the template defaults to an empty string
+            } else {
+                parameters.push(json!({
+                    "name": input.id,
+                    "value": format!("{{{{workflow.parameters.{source}}}}}"),
+                }));
+            }
+        }
+
+        if !dependencies.is_empty() {
+            task.insert("dependencies".to_string(), json!(dependencies));
+        }
+        task.insert(
+            "arguments".to_string(),
+            json!({ "parameters": parameters, "artifacts": artifacts }),
+        );
+        JValue::Object(task)
+    }
+
+    fn template(&mut self, step: &WorkflowStep) -> JValue {
+        let step_id = step.id.clone().unwrap_or_default();
+        let tool = match &step.run {
+            StepRun::CommandLineTool(tool) => tool,
+            StepRun::Workflow(_) => {
+                self.unsupported(&step_id, "nested workflow");
+                return json!({ "name": step_id });
+            }
+            StepRun::Operation(_) => {
+                self.unsupported(&step_id, "abstract operation cannot be executed");
+                return json!({ "name": step_id });
+            }
+            StepRun::Path(path) => {
+                self.unsupported(&step_id, &format!("unresolved run '{path}'"));
+                return json!({ "name": step_id });
+            }
+        };
+
+        let mut parameters = vec![];
+        let mut artifacts = vec![];
+        for input in &tool.inputs {
+            if is_path_type(&input.r#type) {
+                let mut artifact = json!({ "name": input.id, "path": input_path(&input.id) });
+                if input.r#type.is_optional() {
+                    artifact["optional"] = json!(true);
+                }
+                artifacts.push(artifact);
+            } else if contains_path_type(&input.r#type) {
+                self.unsupported(&step_id, &nested_path_input(&input.id, &input.r#type));
+            } else {
+                match input
+                    .default
+                    .as_ref()
+                    .and_then(|d| serde_json::to_value(d).ok())
+                {
+                    Some(default) => parameters
+                        .push(json!({ "name": input.id, "default": parameter_value(&default) })),
+                    None if input.r#type.is_optional() => {
+                        parameters.push(json!({ "name": input.id, "default": "" }))
+                    }
+                    None => parameters.push(json!({ "name": input.id })),
+                }
+            }
+        }
+
+        let mut output_artifacts = vec![];
+        for output in &tool.outputs {
+            let binding = output.output_binding.as_ref();
+            if !is_path_type(&output.r#type) {
+                self.unsupported(&step_id, &format!("non-File output '{}'", output.id));
+            } else if binding.is_some_and(|b| b.output_eval.is_some()) {
+                self.unsupported(&step_id, &format!("outputEval of output '{}'", output.id));
+            } else {
+                match binding
+                    .and_then(|b| b.glob.as_deref())
+                    .map(parameter_references)
+                {
+                    Some(Some(glob)) => output_artifacts.push(json!({
+                        "name": output.id,
+                        "path": format!("{WORKING_DIR}/{glob}"),
+                    })),
+                    Some(None) => self.unsupported(
+                        &step_id,
+                        &format!("expression in glob of output '{}'", output.id),
+                    ),
+                    None => {
+                        self.unsupported(&step_id, &format!("output '{}' without glob", output.id))
+                    }
+                }
+            }
+        }
+
+        let mut container = Map::new();
+        match tool
+            .inherited_requirement::<DockerRequirement>(&[self.workflow])
+            .and_then(DockerRequirement::image)
+        {
+            Some(image) => {
+                container.insert("image".to_string(), json!(image));
+            }
+            None => self.unsupported(&step_id, "missing DockerRequirement"),
+        }
+        for (stream, file_name) in [
+            ("stdin", tool.stdin.as_deref()),
+            ("stdout", tool.stream_file("stdout")),
+            ("stderr", tool.stream_file("stderr")),
+        ] {
+            if file_name.is_some() {
+                self.unsupported(&step_id, &format!("{stream} redirection"));
+            }
+        }
+        let command_line = self.command_line(step, tool);
+        if command_line.is_shell() {
+            let mut env = BTreeMap::new();
+            let script = command_line.shell_script(|arg| shell_quote_parameters(arg, &mut env));
+            container.insert("command".to_string(), json!(["sh", "-c"]));
+            container.insert("args".to_string(), json!([script]));
+            if !env.is_empty() {
+                let env: Vec<JValue> = env
+                    .into_iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect();
+                container.insert("env".to_string(), json!(env));
+            }
+        } else {
+            if tool.base_command.is_some() {
+                container.insert("command".to_string(), json!(command_line.base_command()));
+            }
+            container.insert("args".to_string(), json!(command_line.args()));
+        }
+        container.insert("workingDir".to_string(), json!(WORKING_DIR));
+        if let Some(resources) = tool.inherited_requirement::<ResourceRequirement>(&[self.workflow])
+        {
+            container.insert("resources".to_string(), resources_value(resources));
+        }
+
+        let mut template = Map::new();
+        template.insert("name".to_string(), json!(step_id));
+        template.insert(
+            "inputs".to_string(),
+            json!({ "parameters": parameters, "artifacts": artifacts }),
+        );
+        template.insert(
+            "outputs".to_string(),
+            json!({ "artifacts": output_artifacts }),
+        );
+        template.insert("container".to_string(), JValue::Object(container));
+        if let Some(limit) = tool.inherited_requirement::<ToolTimeLimit>(&[self.workflow]) {
+            match &limit.timelimit {
+                Timelimit::Seconds(seconds) => {
+                    template.insert("activeDeadlineSeconds".to_string(), json!(seconds));
+                }
+                Timelimit::Expression(_) => {
+                    self.unsupported(&step_id, "expression in ToolTimeLimit")
+                }
+            }
+        }
+        JValue::Object(template)
+    }
+
+    /// Builds the command line of the tool from `arguments` and `inputBinding`s, inputs are
+    /// referenced by template parameters and artifact paths. Booleans and arrays are bound
+    /// by their values since they change the number of arguments, absent inputs are skipped.
+    fn command_line(&mut self, step: &WorkflowStep, tool: &CommandLineTool) -> CommandLine {
+        let step_id = step.id.as_deref().unwrap_or_default();
+        let mut command_line = tool.command_line(&[self.workflow]);
+        for argument in tool.arguments.iter().flatten() {
+            let (binding, value) = match argument {
+                Argument::String(value) => (None, value),
+                Argument::Binding(binding) => match &binding.value_from {
+                    Some(value) => (Some(binding), value),
+                    None => continue,
+                },
+            };
+            let Some(value) = parameter_references(value) else {
+                self.unsupported(step_id, &format!("expression in argument '{value}'"));
+                continue;
+            };
+            match binding {
+                Some(binding) => self.bind(
+                    step_id,
+                    &mut command_line,
+                    binding,
+                    CwlValueType::String(value),
+                ),
+                None => {
+                    command_line.arg(&value);
+                }
+            }
+        }
+        for input in &tool.inputs {
+            let Some(binding) = &input.input_binding else {
+                continue;
+            };
+            if !is_path_type(&input.r#type) && contains_path_type(&input.r#type) {
+                // Reported with the inputs of the template
+                continue;
+            }
+            if binding.value_from.is_some() {
+                self.unsupported(
+                    step_id,
+                    &format!("valueFrom of input binding '{}'", input.id),
+                );
+                continue;
+            }
+            let value = match self.static_value(step, input) {
+                StaticValue::Absent => continue,
+                StaticValue::Value(value)
+                    if matches!(*value, CwlValueType::Boolean(_) | CwlValueType::Array(_)) =>
+                {
+                    *value
+                }
+                StaticValue::Value(_) | StaticValue::Runtime
+                    if is_boolean_type(&input.r#type) || input.r#type.item_type().is_some() =>
+                {
+                    self.unsupported(
+                        step_id,
+                        &format!("boolean or array input '{}' set at runtime", input.id),
+                    );
+                    continue;
+                }
+                _ if is_path_type(&input.r#type) => CwlValueType::String(input_path(&input.id)),
+                _ => CwlValueType::String(format!("{{{{inputs.parameters.{}}}}}", input.id)),
+            };
+            self.bind(step_id, &mut command_line, binding, value);
+        }
+        command_line
+    }
+
+    /// Binds the `value` by the `binding` unless its position is given by an expression.
+    fn bind(
+        &mut self,
+        step_id: &str,
+        command_line: &mut CommandLine,
+        binding: &InputBinding,
+        value: CwlValueType,
+    ) {
+        if binding
+            .position
+            .as_ref()
+            .is_some_and(|p| p.index().is_none())
+        {
+            self.unsupported(step_id, "expression in binding position");
+        } else {
+            command_line.bind(binding, &value);
+        }
+    }
+
+    /// Returns the value of the tool `input` known before the workflow runs: the value of
+    /// the workflow input it's connected to, or the default of the workflow input, of the
+    /// step input or of the tool input.
+    fn static_value(&self, step: &WorkflowStep, input: &CommandInputParameter) -> StaticValue {
+        let step_input = step
+            .r#in
+            .iter()
+            .find(|step_input| step_input.id == input.id);
+        if step_input.is_some_and(|step_input| step_input.value_from.is_some()) {
+            return StaticValue::Runtime;
+        }
+        let value = match step_input.and_then(|step_input| step_input.source.as_ref()) {
+            Some(Source::SingleSource(source)) => {
+                let source = source.trim_start_matches('#');
+                if source.contains('/') || scatter_inputs(step).contains(&input.id.as_str()) {
+                    return StaticValue::Runtime;
+                }
+                self.values.get(source).cloned().or_else(|| {
+                    self.workflow
+                        .input(source)
+                        .and_then(|input| input.default.as_ref())
+                        .and_then(any_value)
+                })
+            }
+            Some(Source::MultiSources(_)) => return StaticValue::Runtime,
+            None => None,
+        };
+        value
+            .or_else(|| {
+                step_input
+                    .and_then(|step_input| step_input.default.as_ref())
+                    .and_then(any_value)
+            })
+            .or_else(|| input.default.as_ref().and_then(any_value))
+            .map_or(StaticValue::Absent, |value| {
+                StaticValue::Value(Box::new(value))
+            })
+    }
+
+    /// Returns artifact located by the value of the workflow input or by its default,
+    /// `None` if the input has neither.
+    fn artifact_location(&self, input_id: &str) -> Result<Option<Map<String, JValue>>, String> {
+        let value = self
+            .values
+            .get(input_id)
+            .and_then(|value| serde_json::to_value(value).ok())
+            .filter(|value| !value.is_null())
+            .or_else(|| {
+                self.workflow
+                    .input(input_id)
+                    .and_then(|input| input.default.as_ref())
+                    .and_then(|default| serde_json::to_value(default).ok())
+            });
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        let location = value
+            .get("location")
+            .and_then(JValue::as_str)
+            .ok_or_else(|| format!("missing location of File input '{input_id}'"))?;
+
+        match self.opts.artifact_mapper.map(location) {
+            Some(JValue::Object(artifact)) => Ok(Some(artifact)),
+            _ => Err(format!(
+                "unsupported location '{location}' of input '{input_id}'"
+            )),
+        }
+    }
+
+    fn unsupported(&mut self, step: &str, feature: &str) {
+        self.unsupported.push(UnsupportedFeature {
+            step: step.to_string(),
+            feature: feature.to_string(),
+        });
+    }
+}
+
+/// Value of a tool input as far as it's known when the workflow is converted.
+enum StaticValue {
+    Value(Box<CwlValueType>),
+    Absent,
+    /// Given by a step output or a scatter item.
+    Runtime,
+}
+
+/// Returns ids of the step inputs it's scattered over.
+fn scatter_inputs(step: &WorkflowStep) -> Vec<&str> {
+    match &step.scatter {
+        Some(Scatter::Parameter(parameter)) => vec![parameter.trim_start_matches('#')],
+        Some(Scatter::Parameters(parameters)) => parameters
+            .iter()
+            .map(|parameter| parameter.trim_start_matches('#'))
+            .collect(),
+        None => vec![],
+    }
+}
+
+fn any_value(value: &Any) -> Option<CwlValueType> {
+    let Any::Any(value) = value;
+    serde_yaml::from_value(value.clone()).ok()
+}
+
+/// Returns `true` for `boolean` types, including optional ones.
+fn is_boolean_type(r#type: &CwlSchemaType) -> bool {
+    match r#type {
+        CwlSchemaType::Any(name) => name == "boolean",
+        CwlSchemaType::Array(types) => {
+            match types.iter().filter(|t| !t.is_null()).collect::<Vec<_>>()[..] {
+                [single] => is_boolean_type(single),
+                _ => false,
+            }
+        }
+        CwlSchemaType::Record(_) | CwlSchemaType::Enum(_) | CwlSchemaType::Map(_) => false,
+    }
+}
+
+/// Returns `true` for `File` and `Directory` types, including optional ones.
+fn is_path_type(r#type: &CwlSchemaType) -> bool {
+    match r#type {
+        CwlSchemaType::Any(name) => name == "File" || name == "Directory",
+        CwlSchemaType::Array(types) => {
+            match types.iter().filter(|t| !t.is_null()).collect::<Vec<_>>()[..] {
+                [single] => is_path_type(single),
+                _ => false,
+            }
+        }
+        CwlSchemaType::Record(_) | CwlSchemaType::Enum(_) | CwlSchemaType::Map(_) => false,
+    }
+}
+
+/// Returns `true` if values of the type contain `File`s or `Directory`s, e.g. items of
+/// an array or fields of a record.
+fn contains_path_type(r#type: &CwlSchemaType) -> bool {
+    match r#type {
+        CwlSchemaType::Any(_) => is_path_type(r#type),
+        CwlSchemaType::Array(types) => types.iter().any(contains_path_type),
+        CwlSchemaType::Record(record) => record
+            .fields
+            .fields()
+            .iter()
+            .any(|field| contains_path_type(&field.r#type)),
+        CwlSchemaType::Enum(_) => false,
+        CwlSchemaType::Map(map) => map.values().any(contains_path_type),
+    }
+}
+
+/// Describes an input whose `File`s or `Directory`s are nested in its value, they can be
+/// passed neither as a parameter nor as a single artifact.
+fn nested_path_input(input_id: &str, r#type: &CwlSchemaType) -> String {
+    if r#type.item_type().is_some() {
+        format!("array of File input '{input_id}'")
+    } else {
+        format!("File nested in input '{input_id}'")
+    }
+}
+
+/// Quotes `arg` for POSIX shells like `shell_quote`, but references parameters as
+/// environment variables collected into `env`, e.g. `--name={{inputs.parameters.name}}`
+/// becomes `--name="$INPUTS_NAME"`. Argo substitutes parameters verbatim, so their values
+/// can't be quoted in the command line itself.
+fn shell_quote_parameters(arg: &str, env: &mut BTreeMap<String, String>) -> String {
+    const PREFIX: &str = "{{inputs.parameters.";
+    let mut quoted = String::new();
+    let mut rest = arg;
+    while let Some((start, end)) = rest
+        .find(PREFIX)
+        .and_then(|start| Some((start, rest[start..].find("}}")? + start)))
+    {
+        if start > 0 {
+            quoted.push_str(&shell_quote(&rest[..start]));
+        }
+        let name: String = rest[start + PREFIX.len()..end]
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let name = format!("INPUTS_{name}");
+        quoted.push_str(&format!("\"${name}\""));
+        env.insert(name, rest[start..end + 2].to_string());
+        rest = &rest[end + 2..];
+    }
+    if !rest.is_empty() || quoted.is_empty() {
+        quoted.push_str(&shell_quote(rest));
+    }
+    quoted
+}
+
+fn input_path(input_id: &str) -> String {
+    format!("{INPUTS_DIR}/{input_id}")
+}
+
+fn parameter_value(value: &JValue) -> String {
+    match value {
+        JValue::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Replaces `$(inputs.<id>)` references with Argo `{{inputs.parameters.<id>}}`,
+/// returns `None` if `expression` contains any other CWL expression.
+fn parameter_references(expression: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = expression;
+    while let Some(start) = rest.find("$(") {
+        let end = rest[start..].find(')')? + start;
+        let id = rest[start + 2..end].strip_prefix("inputs.")?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        result.push_str(&rest[..start]);
+        result.push_str(&format!("{{{{inputs.parameters.{id}}}}}"));
+        rest = &rest[end + 1..];
+    }
+    if rest.contains("${") {
+        return None;
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn resources_value(resources: &ResourceRequirement) -> JValue {
+    json!({
+        "requests": {
+            "cpu": resources.cores_min.to_string(),
+            "memory": format!("{}Mi", resources.ram_min),
+            "ephemeral-storage": format!(
+                "{}Mi",
+                u64::from(resources.tmpdir_min) + u64::from(resources.outdir_min)
+            ),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use rstest::rstest;
+
+    fn workflow(path: &str) -> Workflow {
+        match CwlSchema::from_path(path).unwrap() {
+            CwlSchema::Workflow(workflow) => workflow,
+            _ => panic!("Expected Workflow document"),
+        }
+    }
+
+    #[rstest]
+    fn test_to_argo_workflow_snapshot(#[values(false, true)] fragment_sources: bool) {
+        let mut yaml = std::fs::read_to_string("test_data/cwl/wf-argo-schema.yml").unwrap();
+        if fragment_sources {
+            for source in ["reference", "index/index", "samples", "threads"] {
+                yaml = yaml.replace(
+                    &format!("source: {source}\n"),
+                    &format!("source: '#{source}'\n"),
+                );
+            }
+            yaml = yaml.replace("scatter: sample\n", "scatter: '#sample'\n");
+        }
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_path("test_data/cwl/wf-argo-values.yml").unwrap();
+        let opts = ArgoOptions {
+            namespace: Some("zefiro".to_string()),
+            ..Default::default()
+        };
+
+        let manifest = to_argo_workflow(&workflow, &values, &opts).unwrap();
+        let expected: Value = serde_yaml::from_reader(
+            std::fs::File::open("test_data/argo/wf-argo-workflow.yml").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest, expected);
+    }
+
+    #[test]
+    fn test_to_argo_workflow_unsupported_features() {
+        let workflow = workflow("test_data/cwl/wf-step-schema.yml");
+        let values = CwlValues::from_string("step__in_file: []\nstep__out_file: out.txt").unwrap();
+
+        let error = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap_err();
+        let CwlError::Unsupported(features) = &error else {
+            panic!("Expected Unsupported error, got: {error}");
+        };
+        let features: Vec<String> = features.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            features,
+            vec![
+                "step 'step': scatter over File input 'in_file'",
+                "step 'step': outputEval of output 'out_file'",
+                "step 'step': expression in ToolTimeLimit",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_conditional_steps() {
+        let yaml = std::fs::read_to_string("test_data/cwl/wf-conditional-schema.yml")
+            .unwrap()
+            .replace(
+                "      test: test\n",
+                "      test:\n        source: test\n        pickValue: first_non_null\n",
+            )
+            .replace(
+                "      in1: val\n      test",
+                "      in1:\n        source: val\n        linkMerge: merge_flattened\n      test",
+            );
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("val: 2\ntest: true").unwrap();
+
+        let error = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap_err();
+        let CwlError::Unsupported(features) = &error else {
+            panic!("Expected Unsupported error, got: {error}");
+        };
+        let features: Vec<String> = features.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            features[..4],
+            [
+                "step 'step1': when condition",
+                "step 'step2': when condition",
+                "step 'step2': linkMerge of input 'in1'",
+                "step 'step2': pickValue of input 'test'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_operation() {
+        let workflow = workflow("test_data/cwl/wf-operation-schema.yml");
+        let values = CwlValues::from_string("reads: {class: File, location: reads.fq}").unwrap();
+
+        let error = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "CWL document uses unsupported features:\n  \
+             - step 'align': abstract operation cannot be executed\n  \
+             - step 'summarize': abstract operation cannot be executed"
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_shell_command() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: count
+inputs:
+  - id: pattern
+    type: string
+outputs: []
+requirements: []
+steps:
+  - id: count
+    in:
+      - id: pattern
+        source: pattern
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: [grep, -h]
+      arguments:
+        - valueFrom: "*.log"
+          position: 2
+        - valueFrom: "|"
+          position: 3
+          shellQuote: false
+        - valueFrom: wc -l
+          position: 4
+          shellQuote: false
+      inputs:
+        - id: pattern
+          type: string
+          inputBinding:
+            position: 1
+      outputs: []
+      requirements:
+        - class: DockerRequirement
+          dockerPull: debian:12.8
+        - class: ShellCommandRequirement
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("pattern: error").unwrap();
+
+        let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+        let template = manifest["spec"]["templates"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .find(|template| template["name"] == "count")
+            .unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&template["container"]["command"]).unwrap(),
+            "- sh\n- -c\n"
+        );
+        assert_eq!(
+            template["container"]["args"][0],
+            "grep -h \"$INPUTS_PATTERN\" '*.log' | wc -l"
+        );
+        assert_eq!(template["container"]["env"][0]["name"], "INPUTS_PATTERN");
+        assert_eq!(
+            template["container"]["env"][0]["value"],
+            "{{inputs.parameters.pattern}}"
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_requirements_in_hints() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: hinted
+inputs: []
+outputs: []
+requirements: []
+steps:
+  - id: hello
+    in: []
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: [echo, hello]
+      inputs: []
+      outputs: []
+      hints:
+        DockerRequirement:
+          dockerPull: debian:12.8
+        ResourceRequirement:
+          coresMin: 2
+          ramMin: 512
+        ToolTimeLimit:
+          timelimit: 60
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("{}").unwrap();
+
+        let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+        let template = manifest["spec"]["templates"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .find(|template| template["name"] == "hello")
+            .unwrap();
+        assert_eq!(template["container"]["image"], "debian:12.8");
+        assert_eq!(template["container"]["resources"]["requests"]["cpu"], "2");
+        assert_eq!(
+            template["container"]["resources"]["requests"]["memory"],
+            "512Mi"
+        );
+        assert_eq!(template["activeDeadlineSeconds"], 60);
+    }
+
+    #[test]
+    fn test_to_argo_workflow_without_id() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+inputs: []
+outputs: []
+requirements: []
+steps:
+  - id: hello
+    in: []
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: [echo, hello]
+      inputs: []
+      outputs: []
+      requirements:
+        DockerRequirement:
+          dockerPull: debian:12.8
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("{}").unwrap();
+
+        let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+        assert_eq!(manifest["metadata"]["generateName"], "workflow-");
+    }
+
+    #[test]
+    fn test_to_argo_workflow_runtime_boolean_and_array() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: flags
+inputs:
+  - id: modes
+    type: boolean[]
+outputs: []
+requirements:
+  - class: ScatterFeatureRequirement
+steps:
+  - id: run
+    in:
+      - id: verbose
+        source: modes
+      - id: tags
+        source: first/tags
+    out: []
+    scatter: verbose
+    run:
+      class: CommandLineTool
+      baseCommand: [run]
+      inputs:
+        - id: verbose
+          type: boolean
+          inputBinding:
+            prefix: --verbose
+        - id: tags
+          type: string[]
+          inputBinding:
+            prefix: --tag
+      outputs: []
+      requirements:
+        DockerRequirement:
+          dockerPull: debian:12.8
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("modes: [true, false]").unwrap();
+
+        let error = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap_err();
+        let CwlError::Unsupported(features) = &error else {
+            panic!("Expected Unsupported error, got: {error}");
+        };
+        let features: Vec<String> = features.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            features[features.len() - 2..],
+            [
+                "step 'run': boolean or array input 'verbose' set at runtime",
+                "step 'run': boolean or array input 'tags' set at runtime",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_requirements_of_workflow() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: inherited
+inputs: []
+outputs: []
+requirements: []
+hints:
+  DockerRequirement:
+    dockerPull: debian:12.8
+steps:
+  - id: hello
+    in: []
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: [echo, hello]
+      inputs: []
+      outputs: []
+  - id: bye
+    in: []
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: [echo, bye]
+      inputs: []
+      outputs: []
+      requirements:
+        DockerRequirement:
+          dockerPull: alpine:3.20
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("{}").unwrap();
+
+        let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+        let image = |name: &str| {
+            manifest["spec"]["templates"]
+                .as_sequence()
+                .unwrap()
+                .iter()
+                .find(|template| template["name"] == name)
+                .unwrap()["container"]["image"]
+                .clone()
+        };
+        assert_eq!(image("hello"), "debian:12.8");
+        assert_eq!(image("bye"), "alpine:3.20");
+    }
+
+    #[test]
+    fn test_to_argo_workflow_nested_files() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: merge
+inputs:
+  - id: parts
+    type: File[]
+outputs: []
+requirements: []
+steps:
+  - id: merge
+    in:
+      - id: parts
+        source: parts
+      - id: header
+        default:
+          class: File
+          location: s3://bucket/header.txt
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: cat
+      inputs:
+        - id: header
+          type: File
+        - id: parts
+          type: File[]
+          inputBinding:
+            position: 1
+      outputs: []
+      requirements:
+        DockerRequirement:
+          dockerPull: debian:12.8
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("parts: []").unwrap();
+
+        let error = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "CWL document uses unsupported features:\n  \
+             - step 'merge': File default of input 'header'\n  \
+             - step 'merge': array of File input 'parts'"
+        );
+    }
+
+    #[test]
+    fn test_to_argo_workflow_default_and_optional_files() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: annotate
+inputs:
+  - id: reference
+    type: File
+    default:
+      class: File
+      location: s3://bucket/reference.fa
+  - id: annotation
+    type: File?
+outputs: []
+requirements: []
+steps:
+  - id: annotate
+    in:
+      - id: reference
+        source: reference
+      - id: annotation
+        source: annotation
+    out: []
+    run:
+      class: CommandLineTool
+      baseCommand: annotate
+      inputs:
+        - id: reference
+          type: File
+        - id: annotation
+          type: File?
+      outputs: []
+      requirements:
+        DockerRequirement:
+          dockerPull: debian:12.8
+"#;
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow document");
+        };
+        let values = CwlValues::from_string("{}").unwrap();
+
+        let manifest = to_argo_workflow(&workflow, &values, &ArgoOptions::default()).unwrap();
+        let templates = manifest["spec"]["templates"].as_sequence().unwrap();
+        let task = &templates[0]["dag"]["tasks"][0];
+        assert_eq!(
+            serde_yaml::to_string(&task["arguments"]["artifacts"]).unwrap(),
+            "- name: reference\n  s3:\n    bucket: bucket\n    key: reference.fa\n"
+        );
+        let inputs = &templates[1]["inputs"]["artifacts"];
+        assert_eq!(inputs[0].get("optional"), None);
+        assert_eq!(inputs[1]["name"], "annotation");
+        assert_eq!(inputs[1]["optional"], true);
+    }
+
+    #[rstest]
+    #[case(1024, 1024, "2048Mi")]
+    #[case(4_000_000_000, 1_000_000_000, "5000000000Mi")]
+    fn test_resources_value(
+        #[case] tmpdir_min: u32,
+        #[case] outdir_min: u32,
+        #[case] ephemeral_storage: &str,
+    ) {
+        let resources = ResourceRequirement {
+            cores_min: 2,
+            ram_min: 4096,
+            tmpdir_min,
+            outdir_min,
+        };
+        let value = resources_value(&resources);
+        assert_eq!(value["requests"]["cpu"], "2");
+        assert_eq!(value["requests"]["memory"], "4096Mi");
+        assert_eq!(value["requests"]["ephemeral-storage"], ephemeral_storage);
+    }
+
+    #[rstest]
+    #[case("File", true, true)]
+    #[case("Directory?", true, true)]
+    #[case("File[]", false, true)]
+    #[case("string", false, false)]
+    #[case("{type: record, fields: {reads: File}}", false, true)]
+    #[case("[string, int]", false, false)]
+    fn test_path_types(#[case] r#type: &str, #[case] is_path: bool, #[case] contains_path: bool) {
+        let r#type: CwlSchemaType = serde_yaml::from_str(r#type).unwrap();
+        assert_eq!(is_path_type(&r#type), is_path);
+        assert_eq!(contains_path_type(&r#type), contains_path);
+    }
+
+    #[rstest]
+    #[case("report.html", "report.html", &[])]
+    #[case("{{inputs.parameters.name}}", r#""$INPUTS_NAME""#, &["INPUTS_NAME"])]
+    #[case(
+        "--out={{inputs.parameters.sample-id}}.bam",
+        r#"--out="$INPUTS_SAMPLE_ID".bam"#,
+        &["INPUTS_SAMPLE_ID"]
+    )]
+    #[case(
+        "{{inputs.parameters.a}} and {{inputs.parameters.b}}",
+        r#""$INPUTS_A"' and '"$INPUTS_B""#,
+        &["INPUTS_A", "INPUTS_B"]
+    )]
+    fn test_shell_quote_parameters(
+        #[case] arg: &str,
+        #[case] expected: &str,
+        #[case] names: &[&str],
+    ) {
+        let mut env = BTreeMap::new();
+        assert_eq!(shell_quote_parameters(arg, &mut env), expected);
+        assert_eq!(env.keys().map(String::as_str).collect::<Vec<_>>(), names);
+    }
+
+    #[rstest]
+    #[case("s3://bucket/dir/file.txt", Some(json!({"s3": {"bucket": "bucket", "key": "dir/file.txt"}})))]
+    #[case("gs://bucket/file.txt", Some(json!({"gcs": {"bucket": "bucket", "key": "file.txt"}})))]
+    #[case("https://host/file.txt", Some(json!({"http": {"url": "https://host/file.txt"}})))]
+    #[case("/local/file.txt", None)]
+    fn test_default_artifact_location_mapper(
+        #[case] location: &str,
+        #[case] expected: Option<JValue>,
+    ) {
+        assert_eq!(DefaultArtifactLocationMapper.map(location), expected);
+    }
+
+    #[rstest]
+    #[case("report.html", Some("report.html"))]
+    #[case("$(inputs.sample).bam", Some("{{inputs.parameters.sample}}.bam"))]
+    #[case("$(inputs.in_file.basename)", None)]
+    #[case("${return 'out.txt'}", None)]
+    fn test_parameter_references(#[case] expression: &str, #[case] expected: Option<&str>) {
+        assert_eq!(parameter_references(expression).as_deref(), expected);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::schema::types::Source;
+use crate::schema::workflow::{Workflow, WorkflowOutputParameterOutputSource};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Kind of a node in the `WorkflowGraph`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Input,
+    Step,
+    Output,
+}
+
+impl NodeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Step => "step",
+            Self::Output => "output",
+        }
+    }
+}
+
+/// Represents a workflow input, step or output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Node {
+    pub id: String,
+    pub kind: NodeKind,
+}
+
+impl Node {
+    /// Returns identifier that is unique across all kinds of nodes, e.g. `step:align`.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.kind.as_str(), self.id)
+    }
+}
+
+/// Represents a data dependency between two nodes (see `Node::key`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Represents the data flow of a `Workflow` as a directed acyclic graph.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WorkflowGraph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl WorkflowGraph {
+    /// Builds graph from `source` and `outputSource` references of the `workflow`.
+    ///
+    /// ```
+    /// use zefiro_cwl::graph::dag::WorkflowGraph;
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap();
+    /// if let CwlSchema::Workflow(workflow) = schema {
+    ///     let graph = WorkflowGraph::from_workflow(&workflow);
+    ///     assert_eq!(graph.nodes.len(), 4);
+    /// }
+    /// ```
+    pub fn from_workflow(workflow: &Workflow) -> Self {
+        let mut graph = Self::default();
+
+        for (input_id, _) in workflow.inputs() {
+            graph.add_node(input_id.to_string(), NodeKind::Input);
+        }
+        for (step_id, step) in workflow.steps() {
+            graph.add_node(step_id.to_string(), NodeKind::Step);
+            let to = Node {
+                id: step_id.to_string(),
+                kind: NodeKind::Step,
+            }
+            .key();
+            for source in step.r#in.iter().filter_map(|input| input.source.as_ref()) {
+                let sources = match source {
+                    Source::SingleSource(source) => vec![source],
+                    Source::MultiSources(sources) => sources.iter().collect(),
+                };
+                for source in sources {
+                    graph.add_edge(Self::source_key(source), to.clone());
+                }
+            }
+        }
+        for (output_id, output) in workflow.outputs() {
+            graph.add_node(output_id.to_string(), NodeKind::Output);
+            let to = Node {
+                id: output_id.to_string(),
+                kind: NodeKind::Output,
+            }
+            .key();
+            let sources = match &output.output_source {
+                Some(WorkflowOutputParameterOutputSource::OutputSource(source)) => vec![source],
+                Some(WorkflowOutputParameterOutputSource::OutputSourceArray(sources)) => {
+                    sources.iter().collect()
+                }
+                None => vec![],
+            };
+            for source in sources {
+                graph.add_edge(Self::source_key(source), to.clone());
+            }
+        }
+
+        graph
+    }
+
+    /// Serializes graph into Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph workflow {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Step => "box",
+                NodeKind::Input | NodeKind::Output => "ellipse",
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={}];",
+                node.key(),
+                node.id,
+                shape
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\";", edge.from, edge.to);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Serializes graph into Mermaid flowchart format.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        for node in &self.nodes {
+            let id = Self::mermaid_id(&node.key());
+            let _ = match node.kind {
+                NodeKind::Step => writeln!(mermaid, "    {}[\"{}\"]", id, node.id),
+                NodeKind::Input | NodeKind::Output => {
+                    writeln!(mermaid, "    {}([\"{}\"])", id, node.id)
+                }
+            };
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                mermaid,
+                "    {} --> {}",
+                Self::mermaid_id(&edge.from),
+                Self::mermaid_id(&edge.to)
+            );
+        }
+        mermaid
+    }
+
+    fn add_node(&mut self, id: String, kind: NodeKind) {
+        self.nodes.push(Node { id, kind });
+    }
+
+    fn add_edge(&mut self, from: String, to: String) {
+        let edge = Edge { from, to };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Converts `source` reference (`input_id` or `step_id/output_id`, optionally
+    /// prefixed with `#`) into a node key.
+    fn source_key(source: &str) -> String {
+        let source = source.trim_start_matches('#');
+        match source.split_once('/') {
+            Some((step_id, _)) => Node {
+                id: step_id.to_string(),
+                kind: NodeKind::Step,
+            },
+            None => Node {
+                id: source.to_string(),
+                kind: NodeKind::Input,
+            },
+        }
+        .key()
+    }
+
+    fn mermaid_id(key: &str) -> String {
+        key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+
+    fn workflow() -> Workflow {
+        match CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap() {
+            CwlSchema::Workflow(workflow) => workflow,
+            _ => panic!("Expected Workflow document"),
+        }
+    }
+
+    #[test]
+    fn test_workflow_graph_from_workflow() {
+        let graph = WorkflowGraph::from_workflow(&workflow());
+        let keys: Vec<String> = graph.nodes.iter().map(Node::key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "input:step__in_file",
+                "input:step__out_file",
+                "step:step",
+                "output:step__out_file"
+            ]
+        );
+        assert_eq!(
+            graph.edges,
+            vec![
+                Edge {
+                    from: "input:step__in_file".to_string(),
+                    to: "step:step".to_string()
+                },
+                Edge {
+                    from: "input:step__out_file".to_string(),
+                    to: "step:step".to_string()
+                },
+                Edge {
+                    from: "step:step".to_string(),
+                    to: "output:step__out_file".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workflow_graph_to_dot() {
+        let dot = WorkflowGraph::from_workflow(&workflow()).to_dot();
+        assert!(dot.starts_with("digraph workflow {"));
+        assert!(dot.contains("\"step:step\" [label=\"step\", shape=box];"));
+        assert!(dot.contains("\"step:step\" -> \"output:step__out_file\";"));
+    }
+
+    #[test]
+    fn test_workflow_graph_to_mermaid() {
+        let mermaid = WorkflowGraph::from_workflow(&workflow()).to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("    step_step[\"step\"]"));
+        assert!(mermaid.contains("    input_step__in_file --> step_step"));
+    }
+
+    #[test]
+    fn test_workflow_graph_with_operations() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-operation-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow document");
+        };
+        let graph = WorkflowGraph::from_workflow(&workflow);
+        let keys: Vec<String> = graph.nodes.iter().map(Node::key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "input:reads",
+                "step:align",
+                "step:summarize",
+                "output:report"
+            ]
+        );
+        assert!(graph.edges.contains(&Edge {
+            from: "step:align".to_string(),
+            to: "step:summarize".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_workflow_graph_with_conditional_steps() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-conditional-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow document");
+        };
+        let graph = WorkflowGraph::from_workflow(&workflow);
+        let keys: Vec<String> = graph.nodes.iter().map(Node::key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "input:val",
+                "input:test",
+                "step:step1",
+                "step:step2",
+                "output:out1"
+            ]
+        );
+        assert!(graph.edges.contains(&Edge {
+            from: "step:step2".to_string(),
+            to: "output:out1".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_workflow_graph_with_fragment_sources() {
+        let yaml = std::fs::read_to_string("test_data/cwl/wf-operation-schema.yml").unwrap();
+        let fragment_yaml = yaml
+            .replace("source: reads\n", "source: '#reads'\n")
+            .replace("source: align/bam\n", "source: '#align/bam'\n")
+            .replace(
+                "outputSource: summarize/report\n",
+                "outputSource: '#summarize/report'\n",
+            );
+        assert_ne!(fragment_yaml, yaml);
+        let (CwlSchema::Workflow(workflow), CwlSchema::Workflow(fragment_workflow)) =
+            (yaml.parse().unwrap(), fragment_yaml.parse().unwrap())
+        else {
+            panic!("Expected Workflow documents");
+        };
+
+        let graph = WorkflowGraph::from_workflow(&workflow);
+        let fragment_graph = WorkflowGraph::from_workflow(&fragment_workflow);
+        assert_eq!(fragment_graph.nodes, graph.nodes);
+        assert_eq!(fragment_graph.edges, graph.edges);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::schema::workflow::Workflow;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Decision on the execution of a workflow step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepPlan {
+    /// The step has no `when` condition or the condition evaluated to `true`.
+    Run,
+    /// The `when` condition evaluated to `false`, outputs of the step are null.
+    Skip,
+    /// The step has a `when` condition that is not evaluated yet.
+    Conditional,
+}
+
+/// Plans steps of the `workflow` given results of the evaluated `when` conditions keyed by
+/// step ids, returns `(step_id, plan)` pairs in the declaration order.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zefiro_cwl::graph::plan::{plan_steps, StepPlan};
+/// use zefiro_cwl::CwlSchema;
+///
+/// let schema = CwlSchema::from_path("test_data/cwl/wf-conditional-schema.yml").unwrap();
+/// if let CwlSchema::Workflow(workflow) = schema {
+///     let plan = plan_steps(&workflow, &HashMap::from([("step1", false)]));
+///     assert_eq!(plan, vec![("step1", StepPlan::Skip), ("step2", StepPlan::Conditional)]);
+/// }
+/// ```
+pub fn plan_steps<'a>(
+    workflow: &'a Workflow,
+    conditions: &HashMap<&str, bool>,
+) -> Vec<(&'a str, StepPlan)> {
+    workflow
+        .steps()
+        .map(|(step_id, step)| {
+            let plan = match (&step.when, conditions.get(step_id)) {
+                (None, _) | (Some(_), Some(true)) => StepPlan::Run,
+                (Some(_), Some(false)) => StepPlan::Skip,
+                (Some(_), None) => StepPlan::Conditional,
+            };
+            (step_id, plan)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("wf-step-schema.yml", vec![], vec![StepPlan::Run])]
+    #[case("wf-step-schema.yml", vec![("step", false)], vec![StepPlan::Run])]
+    #[case(
+        "wf-conditional-schema.yml",
+        vec![],
+        vec![StepPlan::Conditional, StepPlan::Conditional]
+    )]
+    #[case(
+        "wf-conditional-schema.yml",
+        vec![("step1", true), ("step2", false)],
+        vec![StepPlan::Run, StepPlan::Skip]
+    )]
+    fn test_plan_steps(
+        #[case] schema: &str,
+        #[case] conditions: Vec<(&str, bool)>,
+        #[case] expected: Vec<StepPlan>,
+    ) {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path(&format!("test_data/cwl/{schema}")).unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let plan = plan_steps(&workflow, &conditions.into_iter().collect());
+        assert_eq!(
+            plan.into_iter().map(|(_, plan)| plan).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
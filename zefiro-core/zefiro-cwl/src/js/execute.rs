@@ -1,4 +1,4 @@
-use anyhow::{Context, Error};
+use crate::error::{CwlError, JsError};
 use deno_core::{serde_json, serde_v8, v8, JsRuntime};
 use serde_json::Value;
 
@@ -8,7 +8,7 @@ pub struct JsExecutor {
 
 impl JsExecutor {
     /// Creates a new `JsExecutor` with given `cwl_inputs` and `cwl_self`.
-    pub fn new(cwl_inputs: &Value, cwl_self: &Value) -> Result<Self, Error> {
+    pub fn new(cwl_inputs: &Value, cwl_self: &Value) -> Result<Self, CwlError> {
         let mut runtime = JsRuntime::new(Default::default());
         let init_script = format!(
             r#"const inputs = {}; const self = {};"#,
@@ -17,22 +17,22 @@ impl JsExecutor {
 
         runtime
             .execute_script("<init>", init_script)
-            .context("Failed to initialize JavaScript context")?;
+            .map_err(|e| JsError::Init(e.into()))?;
 
         Ok(Self { runtime })
     }
 
     /// Executes JavaScript `script` and returns the result as a string.
-    pub fn run(&mut self, script: &str) -> Result<String, Error> {
+    pub fn run(&mut self, script: &str) -> Result<String, CwlError> {
         let result = self
             .runtime
             .execute_script("<eval>", script.to_string())
-            .context("Failed to execute JavaScript expression")?;
+            .map_err(|e| JsError::Execute(e.into()))?;
 
         let scope = &mut self.runtime.handle_scope();
         let local_result = v8::Local::new(scope, result);
         let result_json: serde_json::Value =
-            serde_v8::from_v8(scope, local_result).context("Failed to deserialize result")?;
+            serde_v8::from_v8(scope, local_result).map_err(|e| JsError::Deserialize(e.into()))?;
 
         Ok(result_json.to_string())
     }
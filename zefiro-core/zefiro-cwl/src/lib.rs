@@ -1,10 +1,22 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary_helpers;
+pub mod error;
+pub mod export;
+pub mod graph;
+#[cfg(feature = "js")]
 pub mod js;
-#[doc = include_str!("../README.md")]
+pub mod lint;
+#[cfg_attr(all(feature = "js", feature = "template"), doc = include_str!("../README.md"))]
 pub mod schema;
+pub mod staging;
+#[cfg(feature = "template")]
 pub mod template;
 pub mod values;
 
+pub use crate::error::CwlError;
+#[cfg(feature = "js")]
 pub use crate::js::execute::JsExecutor;
 pub use crate::schema::document::CwlSchema;
+#[cfg(feature = "template")]
 pub use crate::template::render::TemplateRender;
 pub use crate::values::document::CwlValues;
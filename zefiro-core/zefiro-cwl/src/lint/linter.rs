@@ -0,0 +1,594 @@
+pub use crate::error::Severity;
+use crate::schema::command_line_tool::CommandLineTool;
+use crate::schema::document::CwlSchema;
+use crate::schema::operation::Operation;
+use crate::schema::requirements::{
+    DockerRequirement, Requirement, ResourceRequirement, ToolTimeLimit,
+};
+use crate::schema::types::Source;
+use crate::schema::workflow::{StepRun, Workflow, WorkflowOutputParameterOutputSource};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// Style and best-practice checks of CWL documents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// `dockerPull` image has no tag, or uses `latest` tag.
+    UnpinnedImage,
+    /// `CommandLineTool` has no `ResourceRequirement`.
+    MissingResourceRequirement,
+    /// `CommandLineTool` has no `ToolTimeLimit`.
+    MissingToolTimeLimit,
+    /// Document, parameter or step id is not in kebab-case.
+    NonKebabCaseId,
+    /// `Workflow` input has no `doc`.
+    MissingInputDoc,
+    /// `Workflow` input is not used by any step or output.
+    UnusedInput,
+    /// Step output is not used by any step or workflow output.
+    UnusedStepOutput,
+}
+
+impl LintRule {
+    pub const ALL: [Self; 7] = [
+        Self::UnpinnedImage,
+        Self::MissingResourceRequirement,
+        Self::MissingToolTimeLimit,
+        Self::NonKebabCaseId,
+        Self::MissingInputDoc,
+        Self::UnusedInput,
+        Self::UnusedStepOutput,
+    ];
+
+    /// Returns identifier of the rule, e.g. `unpinned-image`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::UnpinnedImage => "unpinned-image",
+            Self::MissingResourceRequirement => "missing-resource-requirement",
+            Self::MissingToolTimeLimit => "missing-tool-time-limit",
+            Self::NonKebabCaseId => "non-kebab-case-id",
+            Self::MissingInputDoc => "missing-input-doc",
+            Self::UnusedInput => "unused-input",
+            Self::UnusedStepOutput => "unused-step-output",
+        }
+    }
+
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Self::NonKebabCaseId | Self::MissingInputDoc => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.id() == s)
+            .ok_or_else(|| {
+                let ids: Vec<&str> = Self::ALL.iter().map(LintRule::id).collect();
+                format!(
+                    "Unknown lint rule '{s}', expected one of: {}",
+                    ids.join(", ")
+                )
+            })
+    }
+}
+
+/// Selects lint rules to run and overrides their severities.
+#[derive(Clone, Debug, Default)]
+pub struct LintConfig {
+    disabled: HashSet<LintRule>,
+    severities: HashMap<LintRule, Severity>,
+}
+
+impl LintConfig {
+    /// Turns off the `rule`.
+    pub fn disable(mut self, rule: LintRule) -> Self {
+        self.disabled.insert(rule);
+        self
+    }
+
+    /// Reports findings of the `rule` with the given `severity`.
+    pub fn severity(mut self, rule: LintRule, severity: Severity) -> Self {
+        self.severities.insert(rule, severity);
+        self
+    }
+
+    pub fn is_enabled(&self, rule: LintRule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+
+    pub fn severity_of(&self, rule: LintRule) -> Severity {
+        self.severities
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+/// Represents a single violation of a lint rule.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub severity: Severity,
+    /// Path to the element in the document, e.g. `steps.align.run.requirements`.
+    pub location: String,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}: {} (help: {})",
+            self.severity, self.rule, self.location, self.message, self.suggestion
+        )
+    }
+}
+
+impl CwlSchema {
+    /// Checks the document against style and best-practice `rules`.
+    ///
+    /// ```
+    /// use zefiro_cwl::lint::linter::{LintConfig, LintRule, Severity};
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/clt-step-schema.yml").unwrap();
+    /// let config = LintConfig::default().severity(LintRule::UnpinnedImage, Severity::Error);
+    /// let findings = schema.lint(&config);
+    /// assert!(findings.iter().all(|f| f.severity != Severity::Error));
+    /// ```
+    pub fn lint(&self, rules: &LintConfig) -> Vec<LintFinding> {
+        let mut linter = Linter {
+            config: rules,
+            findings: vec![],
+        };
+        match self {
+            Self::CommandLineTool(tool) => linter.tool(tool, "", &[]),
+            Self::Workflow(workflow) => linter.workflow(workflow, "", &[]),
+            Self::Operation(operation) => linter.operation(operation),
+        }
+        linter.findings
+    }
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    findings: Vec<LintFinding>,
+}
+
+impl Linter<'_> {
+    /// Requirements of the enclosing `workflows` apply to the tool, their images are
+    /// checked with the workflows.
+    fn tool(&mut self, tool: &CommandLineTool, prefix: &str, workflows: &[&Workflow]) {
+        let requirements = format!("{prefix}requirements");
+        let in_requirements = tool
+            .requirements
+            .iter()
+            .any(|requirement| DockerRequirement::from_tool_requirement(requirement).is_some());
+        self.image(
+            tool.effective_requirement::<DockerRequirement>(),
+            in_requirements,
+            prefix,
+        );
+        if tool
+            .inherited_requirement::<ResourceRequirement>(workflows)
+            .is_none()
+        {
+            self.report(
+                LintRule::MissingResourceRequirement,
+                &requirements,
+                format!("Tool '{}' doesn't declare ResourceRequirement", tool.id),
+                "add ResourceRequirement with coresMin and ramMin",
+            );
+        }
+        if tool
+            .inherited_requirement::<ToolTimeLimit>(workflows)
+            .is_none()
+        {
+            self.report(
+                LintRule::MissingToolTimeLimit,
+                &requirements,
+                format!("Tool '{}' doesn't declare ToolTimeLimit", tool.id),
+                "add ToolTimeLimit to stop hanging executions",
+            );
+        }
+
+        self.kebab_case(&format!("{prefix}id"), &tool.id);
+        for (id, _) in tool.inputs() {
+            self.kebab_case(&format!("{prefix}inputs.{id}"), id);
+        }
+        for (id, _) in tool.outputs() {
+            self.kebab_case(&format!("{prefix}outputs.{id}"), id);
+        }
+    }
+
+    /// Operations have no implementation yet, so only naming rules apply.
+    fn operation(&mut self, operation: &Operation) {
+        self.kebab_case("id", &operation.id);
+        for (id, _) in operation.inputs() {
+            self.kebab_case(&format!("inputs.{id}"), id);
+        }
+        for (id, _) in operation.outputs() {
+            self.kebab_case(&format!("outputs.{id}"), id);
+        }
+    }
+
+    /// Requirements of the `workflow` and the enclosing `workflows` apply to its steps.
+    fn workflow(&mut self, workflow: &Workflow, prefix: &str, workflows: &[&Workflow]) {
+        let in_requirements = workflow
+            .requirements
+            .iter()
+            .any(|requirement| DockerRequirement::from_workflow_requirement(requirement).is_some());
+        self.image(
+            workflow.effective_requirement::<DockerRequirement>(),
+            in_requirements,
+            prefix,
+        );
+
+        let mut sources: HashSet<&str> = HashSet::new();
+        for step in &workflow.steps {
+            for source in step.r#in.iter().filter_map(|input| input.source.as_ref()) {
+                match source {
+                    Source::SingleSource(source) => {
+                        sources.insert(source);
+                    }
+                    Source::MultiSources(many) => sources.extend(many.iter().map(String::as_str)),
+                }
+            }
+        }
+        for output in &workflow.outputs {
+            match &output.output_source {
+                Some(WorkflowOutputParameterOutputSource::OutputSource(source)) => {
+                    sources.insert(source);
+                }
+                Some(WorkflowOutputParameterOutputSource::OutputSourceArray(many)) => {
+                    sources.extend(many.iter().map(String::as_str))
+                }
+                None => {}
+            }
+        }
+        let sources: HashSet<&str> = sources
+            .into_iter()
+            .map(|source| source.trim_start_matches('#'))
+            .collect();
+
+        self.kebab_case(&format!("{prefix}id"), &workflow.id);
+        for (id, input) in workflow.inputs() {
+            let location = format!("{prefix}inputs.{id}");
+            self.kebab_case(&location, id);
+            if input.doc.is_none() {
+                self.report(
+                    LintRule::MissingInputDoc,
+                    &location,
+                    format!("Workflow input '{id}' has no doc"),
+                    "describe the input with doc field",
+                );
+            }
+            if !sources.contains(id) {
+                self.report(
+                    LintRule::UnusedInput,
+                    &location,
+                    format!("Workflow input '{id}' is not used"),
+                    "remove the input or connect it to a step",
+                );
+            }
+        }
+        for (id, _) in workflow.outputs() {
+            self.kebab_case(&format!("{prefix}outputs.{id}"), id);
+        }
+        for (step_id, step) in workflow.steps() {
+            let location = format!("{prefix}steps.{step_id}");
+            self.kebab_case(&location, step_id);
+            for out in &step.out {
+                if !sources.contains(format!("{step_id}/{}", out.id).as_str()) {
+                    self.report(
+                        LintRule::UnusedStepOutput,
+                        &format!("{location}.out.{}", out.id),
+                        format!("Output '{}' of step '{step_id}' is not used", out.id),
+                        "remove the output or connect it to a step or workflow output",
+                    );
+                }
+            }
+            let enclosing: Vec<&Workflow> = workflows.iter().copied().chain([workflow]).collect();
+            match &step.run {
+                StepRun::CommandLineTool(tool) => {
+                    self.tool(tool, &format!("{location}.run."), &enclosing)
+                }
+                StepRun::Workflow(nested) => {
+                    self.workflow(nested, &format!("{location}.run."), &enclosing)
+                }
+                StepRun::Operation(_) | StepRun::Path(_) => {}
+            }
+        }
+    }
+
+    /// Reports unpinned image of the process at `prefix` declared in requirements or hints.
+    fn image(&mut self, docker: Option<&DockerRequirement>, in_requirements: bool, prefix: &str) {
+        let image = docker.and_then(|docker| docker.docker_pull.as_ref());
+        if let Some(image) = image.filter(|image| !is_pinned(image)) {
+            let section = if in_requirements {
+                "requirements"
+            } else {
+                "hints"
+            };
+            self.report(
+                LintRule::UnpinnedImage,
+                &format!("{prefix}{section}"),
+                format!("Docker image '{image}' is not pinned"),
+                "use an explicit version tag or digest instead of 'latest'",
+            );
+        }
+    }
+
+    fn kebab_case(&mut self, location: &str, id: &str) {
+        if !id.is_empty() && !is_kebab_case(id) {
+            self.report(
+                LintRule::NonKebabCaseId,
+                location,
+                format!("Id '{id}' is not in kebab-case"),
+                "use lowercase letters, digits and single dashes",
+            );
+        }
+    }
+
+    fn report(&mut self, rule: LintRule, location: &str, message: String, suggestion: &str) {
+        if self.config.is_enabled(rule) {
+            self.findings.push(LintFinding {
+                rule,
+                severity: self.config.severity_of(rule),
+                location: location.to_string(),
+                message,
+                suggestion: suggestion.to_string(),
+            });
+        }
+    }
+}
+
+/// Returns `true` if `image` has a digest or a tag other than `latest`.
+fn is_pinned(image: &str) -> bool {
+    if image.contains('@') {
+        return true;
+    }
+    let name = image.rsplit('/').next().unwrap_or(image);
+    match name.split_once(':') {
+        Some((_, tag)) => !tag.is_empty() && tag != "latest",
+        None => false,
+    }
+}
+
+fn is_kebab_case(id: &str) -> bool {
+    id.split('-').all(|part| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const TOOL: &str = r#"
+cwlVersion: v1.2
+class: CommandLineTool
+id: tool
+inputs:
+  - id: in-file
+    type: File
+outputs:
+  - id: out-file
+    type: File
+requirements:
+  - class: DockerRequirement
+    dockerPull: registry:5000/tool:1.0
+  - class: ResourceRequirement
+    coresMin: 1
+  - class: ToolTimeLimit
+    timelimit: 60
+"#;
+
+    const WORKFLOW: &str = r#"
+cwlVersion: v1.2
+class: Workflow
+id: wf
+inputs:
+  - id: in-file
+    type: File
+    doc: Input file
+outputs:
+  - id: out-file
+    type: File
+    outputSource: step/out-file
+steps:
+  - id: step
+    in:
+      - id: in-file
+        source: in-file
+    out:
+      - id: out-file
+    run:
+      cwlVersion: v1.2
+      class: CommandLineTool
+      id: tool
+      inputs:
+        - id: in-file
+          type: File
+      outputs:
+        - id: out-file
+          type: File
+      requirements:
+        - class: DockerRequirement
+          dockerPull: tool@sha256:0123
+        - class: ResourceRequirement
+        - class: ToolTimeLimit
+          timelimit: 60
+requirements: []
+"#;
+
+    fn rules(yaml: &str) -> Vec<LintRule> {
+        let schema: CwlSchema = yaml.parse().unwrap();
+        schema
+            .lint(&LintConfig::default())
+            .into_iter()
+            .map(|finding| finding.rule)
+            .collect()
+    }
+
+    #[rstest]
+    #[case(TOOL)]
+    #[case(WORKFLOW)]
+    fn test_lint_clean_documents(#[case] yaml: &str) {
+        assert_eq!(rules(yaml), vec![]);
+    }
+
+    #[rstest]
+    #[case(TOOL.replace("tool:1.0", "tool"), LintRule::UnpinnedImage)]
+    #[case(TOOL.replace("tool:1.0", "tool:latest"), LintRule::UnpinnedImage)]
+    #[case(TOOL.replace("class: ResourceRequirement", "class: WorkReuse\n    enableReuse: true").replace("    coresMin: 1\n", ""), LintRule::MissingResourceRequirement)]
+    #[case(TOOL.replace("  - class: ToolTimeLimit\n    timelimit: 60\n", ""), LintRule::MissingToolTimeLimit)]
+    #[case(TOOL.replace("id: in-file", "id: in_file"), LintRule::NonKebabCaseId)]
+    #[case(WORKFLOW.replace("    doc: Input file\n", ""), LintRule::MissingInputDoc)]
+    #[case(WORKFLOW.replace("        source: in-file\n", ""), LintRule::UnusedInput)]
+    #[case(WORKFLOW.replace("    outputSource: step/out-file\n", ""), LintRule::UnusedStepOutput)]
+    #[case(WORKFLOW.replace("dockerPull: tool@sha256:0123", "dockerPull: tool"), LintRule::UnpinnedImage)]
+    fn test_lint_rule_findings(#[case] yaml: String, #[case] rule: LintRule) {
+        assert_eq!(rules(&yaml), vec![rule]);
+    }
+
+    #[rstest]
+    #[case(TOOL.replace("requirements:", "hints:"), vec![])]
+    #[case(
+        TOOL.replace("requirements:", "hints:").replace("tool:1.0", "tool"),
+        vec![(LintRule::UnpinnedImage, "hints")]
+    )]
+    #[case(
+        WORKFLOW.replace("      requirements:\n", "      hints:\n").replace("tool@sha256:0123", "tool:latest"),
+        vec![(LintRule::UnpinnedImage, "steps.step.run.hints")]
+    )]
+    fn test_lint_requirements_in_hints(
+        #[case] yaml: String,
+        #[case] expected: Vec<(LintRule, &str)>,
+    ) {
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let findings = schema.lint(&LintConfig::default());
+        let found: Vec<(LintRule, &str)> = findings
+            .iter()
+            .map(|finding| (finding.rule, finding.location.as_str()))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_lint_nested_workflow() {
+        let schema = CwlSchema::from_path("test_data/cwl/wf-nested-schema.yml").unwrap();
+        let config = LintConfig::default()
+            .disable(LintRule::NonKebabCaseId)
+            .disable(LintRule::MissingInputDoc);
+        let found: Vec<(LintRule, String)> = schema
+            .lint(&config)
+            .into_iter()
+            .map(|finding| (finding.rule, finding.location))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    LintRule::MissingResourceRequirement,
+                    "steps.inner.run.steps.copy.run.requirements".to_string()
+                ),
+                (
+                    LintRule::MissingToolTimeLimit,
+                    "steps.inner.run.steps.copy.run.requirements".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_workflow_requirements() {
+        let yaml = r#"
+cwlVersion: v1.2
+class: Workflow
+id: count
+inputs:
+  - id: text
+    type: File
+    doc: Text to count words in
+outputs:
+  - id: count
+    type: File
+    outputSource: '#wc/count'
+requirements:
+  ResourceRequirement:
+    coresMin: 1
+hints:
+  DockerRequirement:
+    dockerPull: debian:latest
+  ToolTimeLimit:
+    timelimit: 60
+steps:
+  - id: wc
+    in:
+      - id: text
+        source: '#text'
+    out:
+      - id: count
+    run:
+      class: CommandLineTool
+      id: wc
+      baseCommand: wc
+      inputs:
+        - id: text
+          type: File
+      outputs:
+        - id: count
+          type: stdout
+"#;
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let found: Vec<(LintRule, String)> = schema
+            .lint(&LintConfig::default())
+            .into_iter()
+            .map(|finding| (finding.rule, finding.location))
+            .collect();
+        assert_eq!(found, vec![(LintRule::UnpinnedImage, "hints".to_string())]);
+    }
+
+    #[test]
+    fn test_lint_config() {
+        let schema: CwlSchema = TOOL.replace("tool:1.0", "tool").parse().unwrap();
+
+        let findings = schema.lint(&LintConfig::default());
+        assert_eq!(findings[0].severity, Severity::Warning);
+        assert_eq!(findings[0].location, "requirements");
+
+        let config = LintConfig::default().severity(LintRule::UnpinnedImage, Severity::Error);
+        assert_eq!(schema.lint(&config)[0].severity, Severity::Error);
+
+        let config = LintConfig::default().disable(LintRule::UnpinnedImage);
+        assert!(schema.lint(&config).is_empty());
+    }
+
+    #[rstest]
+    #[case("unpinned-image", Ok(LintRule::UnpinnedImage))]
+    #[case("unknown", Err(()))]
+    fn test_lint_rule_from_str(#[case] id: &str, #[case] expected: Result<LintRule, ()>) {
+        assert_eq!(id.parse::<LintRule>().map_err(|_| ()), expected);
+    }
+}
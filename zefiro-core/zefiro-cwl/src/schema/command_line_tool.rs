@@ -1,12 +1,25 @@
-use crate::schema::requirements::{CommandLineToolRequirement, MINIMAL_CWL_VERSION};
-use crate::schema::types::{Any, CwlSchemaType, Documentation, CLT_CWL_CLASS};
-use serde::{Deserialize, Serialize};
+use crate::error::{Severity, ValidationError};
+use crate::schema::index::{deserialize_parameters, id_errors, IdIndex};
+use crate::schema::requirements::{
+    default_load_listing, deserialize_requirements, CommandLineToolRequirement, Hint,
+    InlineJavascriptRequirement, LoadListingRequirement, NetworkAccess, NetworkAccessValue,
+    Requirement, ShellCommandRequirement, SoftwarePackage, CWL_VERSION_1_0, MINIMAL_CWL_VERSION,
+};
+use crate::schema::types::{
+    javascript_error, Any, CwlSchemaType, CwlTypeDef, Documentation, Extensions, Format,
+    LoadListing, RecordField, CLT_CWL_CLASS,
+};
+use crate::schema::workflow::Workflow;
+use crate::values::types::CwlValueType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_with::skip_serializing_none;
+use std::collections::BTreeMap;
 
 /// This defines the schema of the CWL Command Line Tool Description document.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct CommandLineTool {
     #[serde(default = "CommandLineTool::default_cwl_version")]
@@ -19,12 +32,42 @@ pub struct CommandLineTool {
     pub id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
-    #[serde(default)]
+    pub base_command: Option<BaseCommand>,
+    pub arguments: Option<Vec<Argument>>,
+    pub stdin: Option<String>,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_parameters")]
     pub inputs: Vec<CommandInputParameter>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_parameters")]
     pub outputs: Vec<CommandOutputParameter>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_requirements")]
     pub requirements: Vec<CommandLineToolRequirement>,
+    /// Requirements that the runner may ignore if it doesn't support them.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_requirements"
+    )]
+    pub hints: Vec<Hint>,
+    /// Exit codes that indicate the process completed successfully.
+    pub success_codes: Option<Vec<i32>>,
+    /// Exit codes that indicate the process failed due to a possibly temporary condition.
+    pub temporary_fail_codes: Option<Vec<i32>>,
+    /// Exit codes that indicate the process failed due to a permanent logic error.
+    pub permanent_fail_codes: Option<Vec<i32>>,
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    input_index: IdIndex,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    output_index: IdIndex,
 }
 
 impl CommandLineTool {
@@ -35,12 +78,343 @@ impl CommandLineTool {
     fn default_class() -> String {
         CLT_CWL_CLASS.to_string()
     }
+
+    /// Returns input parameter with the given `id`.
+    pub fn input(&self, id: &str) -> Option<&CommandInputParameter> {
+        self.input_index
+            .get(&self.inputs, id, |input| Some(input.id.as_str()))
+    }
+
+    /// Returns output parameter with the given `id`.
+    pub fn output(&self, id: &str) -> Option<&CommandOutputParameter> {
+        self.output_index
+            .get(&self.outputs, id, |output| Some(output.id.as_str()))
+    }
+
+    /// Returns `true` if the tool declares outbound network access, `false` if it doesn't
+    /// and `None` if access depends on an expression that is not evaluated yet.
+    pub fn needs_network(&self) -> Option<bool> {
+        let network_access = self
+            .effective_requirement::<NetworkAccess>()
+            .map(|access| &access.network_access);
+        match network_access {
+            Some(NetworkAccessValue::Enabled(enabled)) => Some(*enabled),
+            Some(NetworkAccessValue::Expression(_)) => None,
+            // Tools had network access before it was restricted in v1.1
+            None => Some(self.cwl_version == CWL_VERSION_1_0),
+        }
+    }
+
+    /// Iterates over packages of `SoftwareRequirement`s declared in requirements and then
+    /// in hints, a runner can use them to select a container image.
+    pub fn software_packages(&self) -> impl Iterator<Item = &SoftwarePackage> {
+        self.requirements
+            .iter()
+            .chain(self.hints.iter().filter_map(Hint::requirement))
+            .filter_map(|requirement| match requirement {
+                CommandLineToolRequirement::SoftwareRequirement(software) => {
+                    Some(&software.packages)
+                }
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Returns requirement of the type `T` declared in requirements or, if there is none,
+    /// in hints of the tool.
+    pub fn effective_requirement<T: Requirement>(&self) -> Option<&T> {
+        self.requirements
+            .iter()
+            .chain(self.hints.iter().filter_map(Hint::requirement))
+            .find_map(T::from_tool_requirement)
+    }
+
+    /// Returns requirement of the type `T` of the tool run by a step, falling back to
+    /// requirements and hints of the enclosing `workflows` from the innermost one.
+    pub fn inherited_requirement<'a, T: Requirement>(
+        &'a self,
+        workflows: &[&'a Workflow],
+    ) -> Option<&'a T> {
+        self.effective_requirement().or_else(|| {
+            workflows
+                .iter()
+                .rev()
+                .find_map(|workflow| workflow.effective_requirement())
+        })
+    }
+
+    /// Starts the command line of the tool run by a step with its `baseCommand`, it's run
+    /// by a shell if the tool or the enclosing `workflows` have `ShellCommandRequirement`.
+    pub fn command_line(&self, workflows: &[&Workflow]) -> CommandLine {
+        CommandLine {
+            base_command: self
+                .base_command
+                .iter()
+                .flat_map(BaseCommand::parts)
+                .map(str::to_string)
+                .collect(),
+            args: vec![],
+            shell: self
+                .inherited_requirement::<ShellCommandRequirement>(workflows)
+                .is_some(),
+        }
+    }
+
+    /// Returns `loadListing` of the input, falling back to `LoadListingRequirement` of
+    /// requirements or hints and then to the default of the CWL version.
+    pub fn effective_load_listing(&self, input_id: &str) -> LoadListing {
+        self.input(input_id)
+            .and_then(|input| input.load_listing)
+            .or_else(|| {
+                self.effective_requirement::<LoadListingRequirement>()
+                    .and_then(|requirement| requirement.load_listing)
+            })
+            .unwrap_or_else(|| default_load_listing(&self.cwl_version))
+    }
+
+    /// Classifies the exit `code` of the tool: codes listed in `successCodes`,
+    /// `temporaryFailCodes` and `permanentFailCodes` are checked in this order, otherwise
+    /// only `0` is a success.
+    pub fn classify_exit(&self, code: i32) -> ExitClass {
+        let listed = |codes: &Option<Vec<i32>>| codes.iter().flatten().any(|c| *c == code);
+        if listed(&self.success_codes) {
+            ExitClass::Success
+        } else if listed(&self.temporary_fail_codes) {
+            ExitClass::TemporaryFail
+        } else if listed(&self.permanent_fail_codes) || code != 0 {
+            ExitClass::PermanentFail
+        } else {
+            ExitClass::Success
+        }
+    }
+
+    /// Iterates over types declared in `SchemaDefRequirement`s of the tool.
+    pub fn type_defs(&self) -> impl Iterator<Item = &CwlTypeDef> {
+        self.requirements
+            .iter()
+            .filter_map(|requirement| match requirement {
+                CommandLineToolRequirement::SchemaDefRequirement(schema_def) => {
+                    Some(&schema_def.types)
+                }
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Iterates over `(id, input)` pairs in the declaration order.
+    pub fn inputs(&self) -> impl Iterator<Item = (&str, &CommandInputParameter)> {
+        self.inputs.iter().map(|input| (input.id.as_str(), input))
+    }
+
+    /// Iterates over `(id, output)` pairs in the declaration order.
+    pub fn outputs(&self) -> impl Iterator<Item = (&str, &CommandOutputParameter)> {
+        self.outputs
+            .iter()
+            .map(|output| (output.id.as_str(), output))
+    }
+
+    /// Returns the file name the `stream` (`stdout` or `stderr`) is captured to: the declared
+    /// name or the glob of an output declared with the `stream` type.
+    pub fn stream_file(&self, stream: &str) -> Option<&str> {
+        let declared = match stream {
+            "stdout" => &self.stdout,
+            "stderr" => &self.stderr,
+            _ => return None,
+        };
+        declared.as_deref().or_else(|| {
+            self.outputs
+                .iter()
+                .find(|output| output.stream.as_deref() == Some(stream))
+                .and_then(|output| output.output_binding.as_ref()?.glob.as_deref())
+        })
+    }
+
+    /// Replaces `stdout` and `stderr` output types with `File` captured from the stream,
+    /// the stream file name is derived from the output id if the tool doesn't declare it.
+    /// The outputs are written back with the stream type, see `CommandOutputParameter::stream`.
+    pub(crate) fn normalize_stream_outputs(&mut self) {
+        for output in &mut self.outputs {
+            let (stream, file_name) = match &output.r#type {
+                CwlSchemaType::Any(t) if t == "stdout" => ("stdout", &self.stdout),
+                CwlSchemaType::Any(t) if t == "stderr" => ("stderr", &self.stderr),
+                _ => continue,
+            };
+            let glob = file_name
+                .clone()
+                .unwrap_or_else(|| format!("{}.{stream}", output.id));
+            output.r#type = CwlSchemaType::Any("File".to_string());
+            output.output_binding = Some(OutputBinding {
+                glob: Some(glob),
+                output_eval: None,
+            });
+            output.stream = Some(stream.to_string());
+        }
+    }
+
+    /// Upgrades the tool to `MINIMAL_CWL_VERSION`, see `CwlSchema::upgrade`.
+    pub fn upgrade(&mut self) {
+        for hint in &mut self.hints {
+            hint.upgrade();
+        }
+        if self.cwl_version == CWL_VERSION_1_0 {
+            if self.effective_requirement::<NetworkAccess>().is_none() {
+                self.requirements
+                    .push(CommandLineToolRequirement::NetworkAccess(NetworkAccess {
+                        network_access: NetworkAccessValue::Enabled(true),
+                    }));
+            }
+            if self
+                .effective_requirement::<LoadListingRequirement>()
+                .is_none()
+            {
+                self.requirements
+                    .push(CommandLineToolRequirement::LoadListingRequirement(
+                        LoadListingRequirement {
+                            load_listing: Some(LoadListing::DeepListing),
+                        },
+                    ));
+            }
+        }
+        self.cwl_version = MINIMAL_CWL_VERSION.to_string();
+    }
+
+    /// Checks that all inputs and outputs have unique ids, lookups by id rely on it.
+    pub fn validate_ids(&self) -> Vec<ValidationError> {
+        let mut errors = id_errors("inputs", self.inputs.iter().map(|i| Some(i.id.as_str())));
+        errors.extend(id_errors(
+            "outputs",
+            self.outputs.iter().map(|o| Some(o.id.as_str())),
+        ));
+        errors
+    }
+
+    /// Checks that every `DockerRequirement` of the tool specifies an image source and
+    /// JavaScript expressions are enabled by `InlineJavascriptRequirement`.
+    pub fn validate_requirements(&self) -> Vec<ValidationError> {
+        self.requirement_errors(false)
+    }
+
+    /// Tools run by workflow steps inherit `InlineJavascriptRequirement` of the workflow.
+    pub(crate) fn requirement_errors(&self, inherited_javascript: bool) -> Vec<ValidationError> {
+        let requirements: Vec<_> = self.requirements.iter().map(Some).collect();
+        let hints: Vec<_> = self.hints.iter().map(Hint::requirement).collect();
+        let mut errors = vec![];
+        for (field, requirements) in [("requirements", requirements), ("hints", hints)] {
+            for (position, requirement) in requirements.into_iter().enumerate() {
+                if let Some(CommandLineToolRequirement::DockerRequirement(docker)) = requirement {
+                    if !docker.has_image_source() {
+                        errors.push(ValidationError {
+                            severity: Severity::Error,
+                            path: format!("{field}[{position}]"),
+                            message: "DockerRequirement requires one of dockerPull, dockerLoad, \
+                                      dockerFile, dockerImport or dockerImageId"
+                                .to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let javascript = inherited_javascript
+            || self
+                .effective_requirement::<InlineJavascriptRequirement>()
+                .is_some();
+        let mut expressions: Vec<(String, &str)> = vec![];
+        for (field, stream) in [
+            ("stdin", &self.stdin),
+            ("stdout", &self.stdout),
+            ("stderr", &self.stderr),
+        ] {
+            expressions.extend(stream.as_deref().map(|s| (field.to_string(), s)));
+        }
+        for (position, argument) in self.arguments.iter().flatten().enumerate() {
+            match argument {
+                Argument::String(value) => {
+                    expressions.push((format!("arguments[{position}]"), value))
+                }
+                Argument::Binding(binding) => expressions.extend(
+                    binding
+                        .value_from
+                        .as_deref()
+                        .map(|value| (format!("arguments[{position}].valueFrom"), value)),
+                ),
+            }
+        }
+        for (position, input) in self.inputs.iter().enumerate() {
+            let value_from = input
+                .input_binding
+                .as_ref()
+                .and_then(|b| b.value_from.as_deref());
+            expressions.extend(
+                value_from
+                    .map(|value| (format!("inputs[{position}].inputBinding.valueFrom"), value)),
+            );
+        }
+        for (position, output) in self.outputs.iter().enumerate() {
+            // Globs of stream outputs are the `stdout` and `stderr` fields checked above
+            let Some(binding) = output
+                .output_binding
+                .as_ref()
+                .filter(|_| output.stream.is_none())
+            else {
+                continue;
+            };
+            for (field, expression) in [
+                ("glob", &binding.glob),
+                ("outputEval", &binding.output_eval),
+            ] {
+                expressions.extend(expression.as_deref().map(|expression| {
+                    (
+                        format!("outputs[{position}].outputBinding.{field}"),
+                        expression,
+                    )
+                }));
+            }
+        }
+        errors.extend(
+            expressions
+                .into_iter()
+                .filter_map(|(path, expression)| javascript_error(path, expression, javascript)),
+        );
+        errors
+    }
+}
+
+/// Program to execute, either a single word or a program followed by its leading arguments.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandLineTool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum BaseCommand {
+    Command(String),
+    CommandWithArguments(Vec<String>),
+}
+
+impl BaseCommand {
+    /// Returns words of the command in the order they appear on the command line.
+    pub fn parts(&self) -> Vec<&str> {
+        match self {
+            Self::Command(command) => vec![command],
+            Self::CommandWithArguments(parts) => parts.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Command line argument that is not bound to any input parameter.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandLineTool
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum Argument {
+    String(String),
+    Binding(InputBinding),
 }
 
 /// Represents an input parameter for a `CommandLineTool`.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandInputParameter
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct CommandInputParameter {
     pub id: String,
@@ -51,13 +425,30 @@ pub struct CommandInputParameter {
     pub input_binding: Option<InputBinding>,
 
     pub default: Option<Any>,
+
+    pub format: Option<Format>,
+
+    pub streamable: Option<bool>,
+
+    pub load_listing: Option<LoadListing>,
+
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
 }
 
 /// Represents an output parameter for a `CommandLineTool`.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandOutputParameter
+///
+/// Outputs declared with the `stdout` or `stderr` type are parsed as `File` outputs with the
+/// stream file name as glob and written back with the stream type.
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase", remote = "Self")]
 pub struct CommandOutputParameter {
     pub id: String,
 
@@ -65,28 +456,279 @@ pub struct CommandOutputParameter {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_binding: Option<OutputBinding>,
+
+    pub format: Option<Format>,
+
+    pub streamable: Option<bool>,
+
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
+
+    /// Stream captured by the output declared with the `stdout` or `stderr` type.
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    stream: Option<String>,
+}
+
+impl CommandOutputParameter {
+    /// Returns the stream, `stdout` or `stderr`, captured by the output if it is declared
+    /// with the stream type.
+    pub fn stream(&self) -> Option<&str> {
+        self.stream.as_deref()
+    }
+}
+
+impl Serialize for CommandOutputParameter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(stream) = &self.stream else {
+            return Self::serialize(self, serializer);
+        };
+        let written = Self {
+            r#type: CwlSchemaType::Any(stream.clone()),
+            output_binding: None,
+            stream: None,
+            ..self.clone()
+        };
+        Self::serialize(&written, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CommandOutputParameter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+}
+
+/// Outcome of a tool execution given by its exit code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitClass {
+    Success,
+    /// The execution may succeed if retried.
+    TemporaryFail,
+    PermanentFail,
 }
 
 /// Describes how to bind an input or output to the command line.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandLineBinding
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct InputBinding {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub position: Option<u32>,
+    pub position: Option<BindingPosition>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefix: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_from: Option<String>,
+
+    /// Whether the value is quoted on the command line, only applies with
+    /// `ShellCommandRequirement`, defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_quote: Option<bool>,
+
+    /// Whether the prefix and the value are separate arguments, defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub separate: Option<bool>,
+
+    /// Joins items of an array value into a single argument.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_separator: Option<String>,
+
+    /// Whether the first 64 KiB of a `File` are loaded into its `contents`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_contents: Option<bool>,
+}
+
+impl InputBinding {
+    /// Builds command line arguments for the bound `value`: `false` and empty arrays are
+    /// omitted, `true` and records add just the prefix, array items are joined by
+    /// `itemSeparator` or added one by one after the prefix. `position` and `shellQuote`
+    /// are applied by `CommandLine::bind`.
+    /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#Input_binding
+    pub fn args(&self, value: &CwlValueType) -> Vec<String> {
+        let prefix = self.prefix.iter().cloned();
+        let value = match value {
+            CwlValueType::Boolean(true) | CwlValueType::Record(_) => return prefix.collect(),
+            CwlValueType::Boolean(false) => return vec![],
+            CwlValueType::Array(items) if items.is_empty() => return vec![],
+            CwlValueType::Array(items) => {
+                let items = items.iter().map(arg_value);
+                match (&self.item_separator, &self.prefix) {
+                    (Some(separator), _) => items.collect::<Vec<_>>().join(separator),
+                    (None, Some(prefix)) if self.separate == Some(false) => {
+                        return items.map(|item| format!("{prefix}{item}")).collect()
+                    }
+                    (None, _) => return prefix.chain(items).collect(),
+                }
+            }
+            value => arg_value(value),
+        };
+        match &self.prefix {
+            Some(prefix) if self.separate == Some(false) => vec![format!("{prefix}{value}")],
+            _ => prefix.chain([value]).collect(),
+        }
+    }
+
+    /// Builds command line arguments for the bound record `value`: the prefix followed by
+    /// arguments of the `fields` that have `inputBinding`, sorted by their position.
+    pub fn record_args(
+        &self,
+        value: &BTreeMap<String, CwlValueType>,
+        fields: &[RecordField],
+    ) -> Vec<String> {
+        let mut bound: Vec<(u32, Vec<String>)> = fields
+            .iter()
+            .filter_map(|field| {
+                let binding = field.input_binding.as_ref()?;
+                let value = value.get(&field.name)?;
+                let position = binding.position.as_ref().and_then(BindingPosition::index);
+                Some((position.unwrap_or(0), binding.args(value)))
+            })
+            .collect();
+        bound.sort_by_key(|(position, _)| *position);
+        self.prefix
+            .iter()
+            .cloned()
+            .chain(bound.into_iter().flat_map(|(_, args)| args))
+            .collect()
+    }
+}
+
+/// Command line of a `CommandLineTool`: its `baseCommand` followed by the bound arguments
+/// sorted by their position.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#Input_binding
+#[derive(Clone, Debug)]
+pub struct CommandLine {
+    base_command: Vec<String>,
+    /// Bound arguments with their position, each paired with `shellQuote` of its binding.
+    args: Vec<(u32, Vec<(String, bool)>)>,
+    shell: bool,
+}
+
+impl CommandLine {
+    /// Binds the `value` by the `binding` at its position, positions given by an expression
+    /// count as `0`. For a binding with `valueFrom`, `value` is its evaluated result.
+    pub fn bind(&mut self, binding: &InputBinding, value: &CwlValueType) -> &mut Self {
+        let position = binding.position.as_ref().and_then(BindingPosition::index);
+        let quote = binding.shell_quote.unwrap_or(true);
+        let args = binding.args(value).into_iter().map(|arg| (arg, quote));
+        self.args.push((position.unwrap_or(0), args.collect()));
+        self
+    }
+
+    /// Adds a plain string argument of `arguments` at position `0`.
+    pub fn arg(&mut self, arg: &str) -> &mut Self {
+        self.args.push((0, vec![(arg.to_string(), true)]));
+        self
+    }
+
+    /// Whether the command line is run by a shell, i.e. with `ShellCommandRequirement`.
+    pub fn is_shell(&self) -> bool {
+        self.shell
+    }
+
+    pub fn base_command(&self) -> &[String] {
+        &self.base_command
+    }
+
+    /// Returns the bound arguments sorted by their position, in the order of binding
+    /// within the same position.
+    pub fn args(&self) -> Vec<String> {
+        self.sorted_args().map(|(arg, _)| arg.clone()).collect()
+    }
+
+    /// Joins the command line into a shell script: words of `baseCommand` and arguments
+    /// are quoted by `quote` unless their binding has `shellQuote: false`.
+    pub fn shell_script(&self, mut quote: impl FnMut(&str) -> String) -> String {
+        let base_command = self.base_command.iter().map(|part| (part, true));
+        let args = self.sorted_args().map(|(arg, quoted)| (arg, *quoted));
+        base_command
+            .chain(args)
+            .map(|(arg, quoted)| if quoted { quote(arg) } else { arg.clone() })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the program and its arguments to execute: `baseCommand` followed by the
+    /// arguments, or `sh -c` with the shell script quoted by `shell_quote`.
+    pub fn argv(&self) -> Vec<String> {
+        if self.shell {
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                self.shell_script(shell_quote),
+            ]
+        } else {
+            self.base_command
+                .iter()
+                .cloned()
+                .chain(self.args())
+                .collect()
+        }
+    }
+
+    fn sorted_args(&self) -> impl Iterator<Item = &(String, bool)> {
+        let mut args: Vec<_> = self.args.iter().collect();
+        args.sort_by_key(|(position, _)| *position);
+        args.into_iter().flat_map(|(_, args)| args)
+    }
+}
+
+/// Quotes `arg` for POSIX shells unless it consists of characters that are never special.
+pub fn shell_quote(arg: &str) -> String {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_plain) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Position of a binding on the command line, arguments are sorted by it.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum BindingPosition {
+    Index(u32),
+    Expression(String),
+}
+
+impl BindingPosition {
+    /// Returns the position if it's not given by an expression.
+    pub fn index(&self) -> Option<u32> {
+        match self {
+            Self::Index(index) => Some(*index),
+            Self::Expression(_) => None,
+        }
+    }
+}
+
+/// Returns string form of a scalar value or path of a `File` or `Directory`.
+fn arg_value(value: &CwlValueType) -> String {
+    match value {
+        CwlValueType::Boolean(value) => value.to_string(),
+        CwlValueType::Int(value) => value.to_string(),
+        CwlValueType::Long(value) => value.to_string(),
+        CwlValueType::Double(value) => value.to_string(),
+        CwlValueType::Float(value) => value.to_string(),
+        CwlValueType::String(value) => value.clone(),
+        CwlValueType::Path(path) => path.path().to_string(),
+        CwlValueType::Array(_) | CwlValueType::Record(_) => String::new(),
+    }
 }
 
 /// Describes how to find and capture output files or values from a CommandLineTool execution.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandOutputBinding
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct OutputBinding {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -95,3 +737,482 @@ pub struct OutputBinding {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_eval: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use crate::schema::requirements::{
+        DockerRequirement, NetworkAccessValue, Timelimit, ToolTimeLimit,
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("prefix: -i", "in.txt", vec!["-i", "in.txt"])]
+    #[case("{prefix: -i, separate: false}", "in.txt", vec!["-iin.txt"])]
+    #[case("{prefix: -i}", "[a, b]", vec!["-i", "a", "b"])]
+    #[case("{prefix: -i, itemSeparator: ','}", "[a, b]", vec!["-i", "a,b"])]
+    #[case("{prefix: -i=, itemSeparator: ',', separate: false}", "[1, 2]", vec!["-i=1,2"])]
+    #[case("itemSeparator: ' '", "[a, b]", vec!["a b"])]
+    #[case("prefix: -i", "[]", vec![])]
+    #[case("prefix: -v", "true", vec!["-v"])]
+    #[case("prefix: -v", "false", vec![])]
+    #[case("prefix: -n", "5", vec!["-n", "5"])]
+    #[case("{}", "{class: File, location: /data/in.txt}", vec!["/data/in.txt"])]
+    #[case(
+        "prefix: -i",
+        "{class: File, location: s3://bucket/in.txt, path: /tmp/in.txt}",
+        vec!["-i", "/tmp/in.txt"]
+    )]
+    #[case(
+        "{}",
+        "{class: Directory, location: s3://bucket/ref, path: /tmp/ref}",
+        vec!["/tmp/ref"]
+    )]
+    #[case("{prefix: -I, separate: false}", "[a, b]", vec!["-Ia", "-Ib"])]
+    #[case("prefix: --sample", "{name: s1}", vec!["--sample"])]
+    fn test_input_binding_args(
+        #[case] binding: &str,
+        #[case] value: &str,
+        #[case] expected: Vec<&str>,
+    ) {
+        let binding: InputBinding = serde_yaml::from_str(binding).unwrap();
+        let value: CwlValueType = serde_yaml::from_str(value).unwrap();
+        assert_eq!(binding.args(&value), expected);
+    }
+
+    #[test]
+    fn test_input_binding_record_args() {
+        let binding: InputBinding = serde_yaml::from_str("prefix: --sample").unwrap();
+        let fields: Vec<RecordField> = serde_yaml::from_str(
+            "[{name: reads, type: File, inputBinding: {position: 2}},\
+             {name: id, type: string, inputBinding: {prefix: --id, position: 1}},\
+             {name: note, type: string}]",
+        )
+        .unwrap();
+        let value: CwlValueType = serde_yaml::from_str(
+            "{id: s1, note: skipped, reads: {class: File, location: /data/s1.fq}}",
+        )
+        .unwrap();
+        let CwlValueType::Record(value) = value else {
+            panic!("Expected record value");
+        };
+        assert_eq!(
+            binding.record_args(&value, &fields),
+            vec!["--sample", "--id", "s1", "/data/s1.fq"]
+        );
+    }
+
+    const SHELL_TOOL: &str = r#"
+cwlVersion: v1.2
+class: CommandLineTool
+baseCommand: [grep, -h]
+arguments:
+  - valueFrom: "*.log"
+    position: 2
+  - valueFrom: "|"
+    position: 3
+    shellQuote: false
+  - valueFrom: wc -l
+    position: 4
+    shellQuote: false
+inputs:
+  - id: pattern
+    type: string
+    inputBinding:
+      position: 1
+outputs: []
+requirements:
+  - class: ShellCommandRequirement
+"#;
+
+    fn command_line(yaml: &str, pattern: &str) -> CommandLine {
+        let CwlSchema::CommandLineTool(tool) = yaml.parse().unwrap() else {
+            panic!("Expected CommandLineTool document");
+        };
+        let mut command_line = tool.command_line(&[]);
+        for argument in tool.arguments.iter().flatten() {
+            match argument {
+                Argument::String(arg) => command_line.arg(arg),
+                Argument::Binding(binding) => {
+                    let value = binding.value_from.clone().unwrap_or_default();
+                    command_line.bind(binding, &CwlValueType::String(value))
+                }
+            };
+        }
+        let binding = tool.inputs[0].input_binding.as_ref().unwrap();
+        command_line.bind(binding, &CwlValueType::String(pattern.to_string()));
+        command_line
+    }
+
+    #[test]
+    fn test_command_line_shell() {
+        let command_line = command_line(SHELL_TOOL, "an error");
+        assert!(command_line.is_shell());
+        assert_eq!(
+            command_line.argv(),
+            vec!["sh", "-c", "grep -h 'an error' '*.log' | wc -l"]
+        );
+        assert_eq!(
+            command_line.shell_script(|arg| format!("<{arg}>")),
+            "<grep> <-h> <an error> <*.log> | wc -l"
+        );
+    }
+
+    #[test]
+    fn test_command_line_without_shell() {
+        let yaml = SHELL_TOOL.replace("requirements:\n  - class: ShellCommandRequirement\n", "");
+        let command_line = command_line(&yaml, "an error");
+        assert!(!command_line.is_shell());
+        assert_eq!(command_line.base_command(), ["grep", "-h"]);
+        assert_eq!(
+            command_line.argv(),
+            vec!["grep", "-h", "an error", "*.log", "|", "wc -l"]
+        );
+    }
+
+    #[rstest]
+    #[case("report.html", "report.html")]
+    #[case("", "''")]
+    #[case("two words", "'two words'")]
+    #[case("it's", r"'it'\''s'")]
+    fn test_shell_quote(#[case] arg: &str, #[case] expected: &str) {
+        assert_eq!(shell_quote(arg), expected);
+    }
+
+    #[rstest]
+    #[case("", 0, ExitClass::Success)]
+    #[case("", 1, ExitClass::PermanentFail)]
+    #[case("successCodes: [0, 3]", 3, ExitClass::Success)]
+    #[case("successCodes: [3]", 0, ExitClass::Success)]
+    #[case("successCodes: [0, 3]", 2, ExitClass::PermanentFail)]
+    #[case("temporaryFailCodes: [75]", 75, ExitClass::TemporaryFail)]
+    #[case("permanentFailCodes: [0]", 0, ExitClass::PermanentFail)]
+    #[case(
+        "{successCodes: [3], temporaryFailCodes: [3, 4]}",
+        3,
+        ExitClass::Success
+    )]
+    #[case(
+        "{successCodes: [3], temporaryFailCodes: [3, 4]}",
+        4,
+        ExitClass::TemporaryFail
+    )]
+    #[case(
+        "{temporaryFailCodes: [75, 1], permanentFailCodes: [1, 2]}",
+        1,
+        ExitClass::TemporaryFail
+    )]
+    #[case(
+        "{temporaryFailCodes: [75, 1], permanentFailCodes: [1, 2]}",
+        2,
+        ExitClass::PermanentFail
+    )]
+    fn test_classify_exit(#[case] codes: &str, #[case] code: i32, #[case] expected: ExitClass) {
+        let codes = codes.trim_start_matches('{').trim_end_matches('}');
+        let tool: CommandLineTool =
+            serde_yaml::from_str(&format!("{{class: CommandLineTool, {codes}}}")).unwrap();
+        assert_eq!(tool.classify_exit(code), expected);
+    }
+
+    #[rstest]
+    #[case("position: 2", Some(2))]
+    #[case("position: $(inputs.rank)", None)]
+    fn test_binding_position(#[case] binding: &str, #[case] expected: Option<u32>) {
+        let binding: InputBinding = serde_yaml::from_str(binding).unwrap();
+        assert_eq!(binding.position.and_then(|p| p.index()), expected);
+    }
+
+    #[rstest]
+    #[case("baseCommand: echo\n", vec!["echo"])]
+    #[case("baseCommand:\n- bwa\n- mem\n", vec!["bwa", "mem"])]
+    fn test_clt_base_command_roundtrip(#[case] base_command: &str, #[case] parts: Vec<&str>) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\nid: tool\n{base_command}inputs: []\noutputs: []\nrequirements: []\n"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(tool.base_command.as_ref().unwrap().parts(), parts);
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+
+    #[test]
+    fn test_clt_arguments_roundtrip() {
+        let yaml = "cwlVersion: v1.2\n\
+                    class: CommandLineTool\n\
+                    id: tool\n\
+                    baseCommand: tar\n\
+                    arguments:\n\
+                    - -x\n\
+                    - position: 2\n  prefix: --threads\n  valueFrom: $(runtime.cores)\n\
+                    - -f\n\
+                    inputs: []\n\
+                    outputs: []\n\
+                    requirements: []\n";
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        let arguments = tool.arguments.as_ref().unwrap();
+        assert!(matches!(&arguments[0], Argument::String(arg) if arg == "-x"));
+        assert!(
+            matches!(&arguments[1], Argument::Binding(b) if b.position == Some(BindingPosition::Index(2)))
+        );
+        assert!(matches!(&arguments[2], Argument::String(arg) if arg == "-f"));
+        assert!(tool.inputs.is_empty());
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+
+    #[rstest]
+    #[case("stdout: $(inputs.sample).log\n", "stdout", "$(inputs.sample).log")]
+    #[case("", "stdout", "log.stdout")]
+    #[case("stderr: errors.txt\n", "stderr", "errors.txt")]
+    fn test_clt_stream_outputs(#[case] stream: &str, #[case] r#type: &str, #[case] glob: &str) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\nid: tool\nstdin: $(inputs.in_file.path)\n{stream}\
+             inputs: []\noutputs:\n- id: log\n  type: {type}\nrequirements: []\n",
+            type = r#type
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(tool.stdin.as_deref(), Some("$(inputs.in_file.path)"));
+        let output = tool.output("log").unwrap();
+        assert_eq!(output.r#type.to_string(), "File");
+        assert_eq!(output.stream(), Some(r#type));
+        let output_binding = output.output_binding.as_ref().unwrap();
+        assert_eq!(output_binding.glob.as_deref(), Some(glob));
+        assert_eq!(tool.stream_file(r#type), Some(glob));
+
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+
+    #[rstest]
+    #[case("", Some(false))]
+    #[case("  - class: NetworkAccess\n    networkAccess: true\n", Some(true))]
+    #[case("  - class: NetworkAccess\n    networkAccess: false\n", Some(false))]
+    #[case(
+        "  - class: NetworkAccess\n    networkAccess: $(inputs.online)\n",
+        None
+    )]
+    #[case(
+        "hints:\n  - class: NetworkAccess\n    networkAccess: true\n",
+        Some(true)
+    )]
+    fn test_clt_needs_network(#[case] requirement: &str, #[case] expected: Option<bool>) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\ninputs: []\noutputs: []\nrequirements:\n\
+             \x20 - class: WorkReuse\n    enableReuse: true\n{requirement}"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(tool.needs_network(), expected);
+
+        let written: CwlSchema = schema.to_string().unwrap().parse().unwrap();
+        let CwlSchema::CommandLineTool(written) = written else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(written.needs_network(), expected);
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("  streamable: true\n", Some(true))]
+    #[case("  streamable: false\n", Some(false))]
+    fn test_streamable_roundtrip(#[case] streamable: &str, #[case] expected: Option<bool>) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\nid: tool\n\
+             inputs:\n- id: reads\n  type: File\n{streamable}\
+             outputs:\n- id: out\n  type: File\n{streamable}requirements: []\n"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(tool.input("reads").unwrap().streamable, expected);
+        assert_eq!(tool.output("out").unwrap().streamable, expected);
+        assert_eq!(schema.to_string().unwrap(), yaml);
+
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: Workflow\nid: wf\n\
+             inputs:\n- type: File\n  id: reads\n{streamable}\
+             outputs:\n- type: File\n  id: out\n  outputSource: reads\n{streamable}\
+             steps: []\nrequirements: []\n"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::Workflow(ref workflow) = schema else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(workflow.input("reads").unwrap().streamable, expected);
+        assert_eq!(workflow.output("out").unwrap().streamable, expected);
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+
+    #[test]
+    fn test_software_requirement_roundtrip() {
+        let yaml = "cwlVersion: v1.2\n\
+                    class: CommandLineTool\n\
+                    id: tool\n\
+                    inputs: []\n\
+                    outputs: []\n\
+                    requirements:\n\
+                    - class: SoftwareRequirement\n  packages:\n  - package: samtools\n    version:\n    - '1.19'\n    - '1.20'\n\
+                    hints:\n\
+                    - class: SoftwareRequirement\n  packages:\n  - package: bwa\n    specs:\n    - https://identifiers.org/biotools:bwa\n";
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        let packages: Vec<&str> = tool
+            .software_packages()
+            .map(|p| p.package.as_str())
+            .collect();
+        assert_eq!(packages, vec!["samtools", "bwa"]);
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+
+    #[rstest]
+    #[case("    samtools:\n      version: ['1.19']\n", Some(vec!["1.19"]), None)]
+    #[case("    samtools: https://identifiers.org/biotools:samtools\n", None, Some(vec!["https://identifiers.org/biotools:samtools"]))]
+    #[case("    samtools:\n", None, None)]
+    fn test_software_requirement_map_form(
+        #[case] packages: &str,
+        #[case] version: Option<Vec<&str>>,
+        #[case] specs: Option<Vec<&str>>,
+    ) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\ninputs: []\noutputs: []\n\
+             hints:\n- class: SoftwareRequirement\n  packages:\n{packages}"
+        );
+        let CwlSchema::CommandLineTool(tool) = yaml.parse().unwrap() else {
+            panic!("Expected CommandLineTool");
+        };
+        let to_strings = |items: Option<Vec<&str>>| {
+            items.map(|items| items.into_iter().map(String::from).collect())
+        };
+        assert_eq!(
+            tool.software_packages().collect::<Vec<_>>(),
+            vec![&SoftwarePackage {
+                package: "samtools".to_string(),
+                version: to_strings(version),
+                specs: to_strings(specs),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_hints_roundtrip() {
+        let yaml = "cwlVersion: v1.2\n\
+                    class: CommandLineTool\n\
+                    id: tool\n\
+                    inputs: []\n\
+                    outputs: []\n\
+                    requirements:\n\
+                    - class: DockerRequirement\n  dockerPull: debian:12.8\n\
+                    hints:\n\
+                    - class: DockerRequirement\n  dockerPull: debian:12.7\n\
+                    - class: NetworkAccess\n  networkAccess: true\n\
+                    - class: cwltool:CUDARequirement\n  cudaVersionMin: '11.4'\n  cudaDeviceCountMin: 1\n";
+        let schema: CwlSchema = yaml.parse().unwrap();
+        assert_eq!(schema.to_string().unwrap(), yaml);
+
+        let CwlSchema::CommandLineTool(tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        assert!(matches!(&tool.hints[2], Hint::Unknown(hint) if hint["cudaDeviceCountMin"] == 1));
+        let docker = tool.effective_requirement::<DockerRequirement>().unwrap();
+        assert_eq!(docker.docker_pull.as_deref(), Some("debian:12.8"));
+        let network = tool.effective_requirement::<NetworkAccess>().unwrap();
+        assert!(matches!(
+            network.network_access,
+            NetworkAccessValue::Enabled(true)
+        ));
+        assert!(tool
+            .effective_requirement::<InlineJavascriptRequirement>()
+            .is_none());
+    }
+
+    #[test]
+    fn test_upgrade_v1_0_tool() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/v1.0/wf-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let tool = workflow.steps[0].run.as_tool().unwrap();
+        assert_eq!(tool.cwl_version, "v1.0");
+        assert_eq!(tool.needs_network(), Some(true));
+        assert_eq!(
+            tool.effective_load_listing("reads"),
+            LoadListing::DeepListing
+        );
+        assert!(matches!(&tool.hints[0], Hint::Unknown(_)));
+
+        let mut tool = tool.clone();
+        tool.upgrade();
+        assert!(matches!(
+            tool.effective_requirement::<ToolTimeLimit>(),
+            Some(ToolTimeLimit {
+                timelimit: Timelimit::Seconds(60)
+            })
+        ));
+        assert_eq!(
+            serde_yaml::to_string(&tool.requirements).unwrap(),
+            "- class: NetworkAccess\n  networkAccess: true\n\
+             - class: LoadListingRequirement\n  loadListing: deep_listing\n"
+        );
+    }
+
+    #[test]
+    fn test_docker_requirement_sources() {
+        let CwlSchema::CommandLineTool(tool) =
+            CwlSchema::from_path("test_data/cwl/clt-docker-schema.yml").unwrap()
+        else {
+            panic!("Expected CommandLineTool");
+        };
+        let docker = tool.effective_requirement::<DockerRequirement>().unwrap();
+        assert_eq!(docker.docker_pull, None);
+        assert_eq!(docker.docker_image_id.as_deref(), Some("report:2.1.0"));
+        assert_eq!(docker.docker_output_directory.as_deref(), Some("/report"));
+        assert!(docker
+            .docker_file
+            .as_deref()
+            .is_some_and(|file| file.starts_with("FROM python:3.12-slim")));
+        assert_eq!(docker.image(), Some("report:2.1.0"));
+        assert!(matches!(
+            tool.hints[0].requirement(),
+            Some(CommandLineToolRequirement::DockerRequirement(hint))
+                if hint.docker_load.as_deref() == Some("https://example.com/images/report-2.1.0.tar")
+        ));
+        assert!(tool.validate_requirements().is_empty());
+    }
+
+    #[test]
+    fn test_docker_requirement_without_image() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\ninputs: []\noutputs: []\nrequirements: []\n\
+                    steps:\n  step:\n    in: {}\n    out: []\n    run:\n      \
+                    class: CommandLineTool\n      inputs: []\n      outputs: []\n      \
+                    requirements:\n        DockerRequirement:\n          \
+                    dockerOutputDirectory: /out\n      hints:\n        \
+                    DockerRequirement:\n          dockerPull: debian:12.8\n";
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(
+            workflow
+                .validate_requirements()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "steps[0].run.requirements[0]: DockerRequirement requires one of dockerPull, \
+              dockerLoad, dockerFile, dockerImport or dockerImageId"
+            ]
+        );
+    }
+}
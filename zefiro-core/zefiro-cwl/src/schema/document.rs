@@ -1,29 +1,37 @@
+use crate::error::{CwlError, Severity, ValidationError};
 use crate::schema::{
     command_line_tool::CommandLineTool,
-    requirements::MINIMAL_CWL_VERSION,
-    types::{CLT_CWL_CLASS, WF_CWL_CLASS},
+    operation::Operation,
+    preprocess::{enter_document, resolve_directives, DocumentStack},
+    requirements::{MINIMAL_CWL_VERSION, SUPPORTED_CWL_VERSIONS},
+    types::{CwlTypeDef, Namespaces, CLT_CWL_CLASS, OPERATION_CWL_CLASS, WF_CWL_CLASS},
     workflow::Workflow,
 };
-use anyhow::{bail, ensure, Error, Result};
 use serde::{Deserialize, Serialize};
 use serde_yaml::{self, Value};
 use std::{
     fs::File,
     io::{BufReader, Write},
+    path::Path,
     str::FromStr,
 };
 
-/// Represents a CWL Schema which can be either a CommandLineTool or a Workflow
+/// Represents a CWL Schema which can be either a CommandLineTool, a Workflow or an Operation
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum CwlSchema {
     CommandLineTool(CommandLineTool),
     Workflow(Workflow),
+    Operation(Operation),
 }
 
 impl CwlSchema {
     /// Deserializes YAML `file` containing CWL values into CwlSchema structure.
     ///
+    /// `$import` and `$include` directives and `run` paths of the workflow steps are resolved
+    /// relative to the including file.
+    ///
     /// ```
     /// use zefiro_cwl::schema::document::CwlSchema;
     ///
@@ -31,27 +39,66 @@ impl CwlSchema {
     ///
     /// let values = CwlSchema::from_path(yaml_file).expect("Failed to deserialize CWL values document");
     /// ```
-    pub fn from_path(path: &str) -> Result<Self> {
-        let reader = BufReader::new(File::open(path)?);
-        Self::from_yaml(serde_yaml::from_reader(reader)?)
+    pub fn from_path(path: &str) -> Result<Self, CwlError> {
+        Self::load(Path::new(path), &mut vec![])
+    }
+
+    /// Loads the document from `path`, `stack` holds the documents that are being loaded.
+    pub(crate) fn load(path: &Path, stack: &mut DocumentStack) -> Result<Self, CwlError> {
+        let reader = BufReader::new(File::open(path).map_err(|source| CwlError::Io {
+            path: path.display().to_string(),
+            source,
+        })?);
+        let value = serde_yaml::from_reader(reader).map_err(CwlError::yaml)?;
+
+        enter_document(path, stack)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut schema = Self::from_yaml(resolve_directives(value, dir, stack)?)?;
+        if let Self::Workflow(workflow) = &mut schema {
+            workflow.resolve_runs_in(dir, stack)?;
+        }
+        stack.pop();
+        Ok(schema)
     }
 
     /// Deserializes a YAML Value into a CwlSchema instance.
-    pub fn from_yaml(value: Value) -> Result<Self> {
+    ///
+    /// Documents of older versions are read with their semantics, see `CwlSchema::upgrade`.
+    pub fn from_yaml(mut value: Value) -> Result<Self, CwlError> {
         let version = value
             .get("cwlVersion")
             .and_then(Value::as_str)
-            .ok_or_else(|| anyhow::anyhow!("Failed to determine CWL specification version."))?;
-        ensure!(
-            MINIMAL_CWL_VERSION == version,
-            "Unsupported CWL version: {version}"
-        );
+            .ok_or(CwlError::MissingVersion)?
+            .to_string();
+        if !SUPPORTED_CWL_VERSIONS.contains(&version.as_str()) {
+            return Err(CwlError::UnsupportedVersion {
+                found: version,
+                supported: SUPPORTED_CWL_VERSIONS,
+            });
+        }
+        if version != MINIMAL_CWL_VERSION {
+            // Inline processes default to the latest version otherwise
+            inherit_version(&mut value, &version);
+        }
 
         match value.get("class").and_then(Value::as_str) {
-            Some(CLT_CWL_CLASS) => Ok(Self::CommandLineTool(serde_yaml::from_value(value)?)),
-            Some(WF_CWL_CLASS) => Ok(Self::Workflow(serde_yaml::from_value(value)?)),
-            Some(class) => bail!("Unsupported CWL document class: {class}"),
-            None => bail!("Failed to determine CWL document class."),
+            Some(CLT_CWL_CLASS) => {
+                let mut tool: CommandLineTool =
+                    serde_yaml::from_value(value).map_err(CwlError::yaml)?;
+                tool.normalize_stream_outputs();
+                Ok(Self::CommandLineTool(tool))
+            }
+            Some(WF_CWL_CLASS) => {
+                let mut workflow: Workflow =
+                    serde_yaml::from_value(value).map_err(CwlError::yaml)?;
+                workflow.normalize_stream_outputs();
+                Ok(Self::Workflow(workflow))
+            }
+            Some(OPERATION_CWL_CLASS) => Ok(Self::Operation(
+                serde_yaml::from_value(value).map_err(CwlError::yaml)?,
+            )),
+            Some(class) => Err(CwlError::UnknownClass(class.to_string())),
+            None => Err(CwlError::MissingClass),
         }
     }
 
@@ -94,14 +141,18 @@ impl CwlSchema {
     ///
     /// let schema = CwlSchema::from_string(yaml_str).expect("Failed to parse CWL document");
     /// ```
-    pub fn from_string(yaml_input: &str) -> Result<Self, Error> {
-        serde_yaml::from_str(yaml_input)
-            .map_err(|e| Error::msg(format!("Failed to parse CWL schema from string: {}", e)))
+    pub fn from_string(yaml_input: &str) -> Result<Self, CwlError> {
+        Self::from_yaml(serde_yaml::from_str(yaml_input).map_err(|e| {
+            CwlError::yaml_with_context("Failed to parse CWL schema from string", e)
+        })?)
     }
 
     /// Serializes CwlSchema structure into `string`.
-    pub fn to_string(&self) -> Result<String> {
-        serde_yaml::to_string(self).map_err(Into::into)
+    pub fn to_string(&self) -> Result<String, CwlError> {
+        serde_yaml::to_string(self).map_err(|source| CwlError::Serialization {
+            document: "CWL schema",
+            source,
+        })
     }
 
     /// Serializes CwlSchema structure and writes it into `file`.
@@ -116,29 +167,159 @@ impl CwlSchema {
     /// let mut writer = BufWriter::new(tmpfile);
     /// schema.to_yaml(writer);
     /// ```
-    pub fn to_yaml<W: Write>(&self, writer: W) -> Result<()> {
-        serde_yaml::to_writer(writer, self).map_err(Into::into)
+    pub fn to_yaml<W: Write>(&self, writer: W) -> Result<(), CwlError> {
+        serde_yaml::to_writer(writer, self).map_err(|source| CwlError::Serialization {
+            document: "CWL schema",
+            source,
+        })
+    }
+
+    /// Upgrades the document and its inline processes to `MINIMAL_CWL_VERSION`, the defaults
+    /// that changed since the declared version are made explicit by requirements.
+    ///
+    /// ```
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let mut schema = CwlSchema::from_path("test_data/cwl/v1.0/wf-schema.yml").unwrap();
+    /// schema.upgrade();
+    /// assert!(schema.to_string().unwrap().starts_with("cwlVersion: v1.2\n"));
+    /// ```
+    pub fn upgrade(&mut self) {
+        match self {
+            Self::CommandLineTool(tool) => tool.upgrade(),
+            Self::Workflow(workflow) => workflow.upgrade(),
+            Self::Operation(operation) => operation.upgrade(),
+        }
+    }
+
+    /// Returns `id` of the document.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::CommandLineTool(tool) => &tool.id,
+            Self::Workflow(workflow) => &workflow.id,
+            Self::Operation(operation) => &operation.id,
+        }
+    }
+
+    /// Returns prefixes declared in `$namespaces` of the document.
+    pub fn namespaces(&self) -> Namespaces {
+        let extensions = match self {
+            Self::CommandLineTool(tool) => &tool.extensions,
+            Self::Workflow(workflow) => &workflow.extensions,
+            Self::Operation(_) => return Namespaces::new(),
+        };
+        let Some(Value::Mapping(namespaces)) = extensions.get("$namespaces") else {
+            return Namespaces::new();
+        };
+        namespaces
+            .iter()
+            .filter_map(|(prefix, iri)| {
+                Some((prefix.as_str()?.to_string(), iri.as_str()?.to_string()))
+            })
+            .collect()
+    }
+
+    /// Looks up a type declared in `SchemaDefRequirement` by reference, e.g. `#sample`.
+    ///
+    /// Types declared by the workflow take precedence over types declared by the step tools.
+    pub fn resolve_type(&self, name: &str) -> Option<&CwlTypeDef> {
+        match self {
+            Self::CommandLineTool(tool) => tool.type_defs().find(|def| def.is_named(name)),
+            Self::Workflow(workflow) => workflow
+                .type_defs()
+                .chain(workflow.steps.iter().flat_map(|step| step.run.type_defs()))
+                .find(|def| def.is_named(name)),
+            Self::Operation(operation) => operation.type_defs().find(|def| def.is_named(name)),
+        }
+    }
+
+    /// Runs all semantic checks of the document: unique ids, declared requirements,
+    /// references to sources and scatter inputs, and types of defaults.
+    ///
+    /// Returns all problems found, including warnings, if any of them is an error. Warnings
+    /// of a valid document are returned by `CwlSchema::problems`.
+    ///
+    /// ```
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap();
+    /// assert!(schema.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let problems = self.problems();
+        if problems
+            .iter()
+            .any(|problem| problem.severity == Severity::Error)
+        {
+            Err(problems)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Collects problems of all severities found by the checks of `CwlSchema::validate`.
+    ///
+    /// ```
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap();
+    /// assert!(schema.problems().is_empty());
+    /// ```
+    pub fn problems(&self) -> Vec<ValidationError> {
+        let checks = match self {
+            Self::CommandLineTool(tool) => vec![tool.validate_ids(), tool.validate_requirements()],
+            Self::Workflow(workflow) => vec![
+                workflow.validate_ids(),
+                workflow.validate_requirements(),
+                workflow.validate_references(),
+            ],
+            Self::Operation(operation) => vec![operation.validate_ids()],
+        };
+        checks
+            .into_iter()
+            .chain([self.validate_defaults()])
+            .flatten()
+            .collect()
+    }
+}
+
+/// Sets `version` of the inline processes run by the workflow steps that don't declare it.
+fn inherit_version(value: &mut Value, version: &str) {
+    let steps: Vec<&mut Value> = match value.get_mut("steps") {
+        Some(Value::Sequence(steps)) => steps.iter_mut().collect(),
+        Some(Value::Mapping(steps)) => steps.values_mut().collect(),
+        _ => return,
+    };
+    for run in steps.into_iter().filter_map(|step| step.get_mut("run")) {
+        if run.is_mapping() && run.get("cwlVersion").is_none() {
+            run["cwlVersion"] = version.into();
+        }
+        inherit_version(run, version);
     }
 }
 
 impl FromStr for CwlSchema {
-    type Err = anyhow::Error;
+    type Err = CwlError;
 
-    fn from_str(s: &str) -> Result<Self> {
-        Self::from_yaml(serde_yaml::from_str(s)?)
+    fn from_str(s: &str) -> Result<Self, CwlError> {
+        Self::from_yaml(serde_yaml::from_str(s).map_err(CwlError::yaml)?)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::types::{CwlSchemaType, LoadListing};
+    use crate::values::document::CwlValues;
     use rstest::rstest;
     use std::io::BufWriter;
-    use std::io::{Error, ErrorKind, Write};
+    use std::io::{Error, Write};
 
     #[rstest]
     #[case("test_data/cwl/clt-step-schema.yml")]
     #[case("test_data/cwl/wf-step-schema.yml")]
+    #[case("test_data/cwl/wf-nested-schema.yml")]
+    #[case("test_data/cwl/clt-docker-schema.yml")]
     fn test_cwlschema_from_path(#[case] file_path: &str) {
         CwlSchema::from_path(file_path).expect("Failed to deserialize CWL schema document");
     }
@@ -146,6 +327,8 @@ mod tests {
     #[rstest]
     #[case("test_data/cwl/clt-step-schema.yml")]
     #[case("test_data/cwl/wf-step-schema.yml")]
+    #[case("test_data/cwl/wf-nested-schema.yml")]
+    #[case("test_data/cwl/clt-docker-schema.yml")]
     fn test_cwlschema_to_yaml(#[case] file_path: &str) {
         let values = CwlSchema::from_path(file_path).expect("Failed to deserialize CWL schema");
         let temp_file = tempfile::NamedTempFile::new().unwrap();
@@ -166,7 +349,7 @@ mod tests {
     struct FailingWriter;
     impl Write for FailingWriter {
         fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
-            Err(Error::new(ErrorKind::Other, "Simulated write error"))
+            Err(Error::other("Simulated write error"))
         }
         fn flush(&mut self) -> std::io::Result<()> {
             Ok(())
@@ -184,4 +367,361 @@ mod tests {
         let schema = CwlSchema::Workflow(Workflow::default());
         assert!(schema.to_yaml(FailingWriter).is_err());
     }
+
+    #[rstest]
+    #[case(
+        "class: CommandLineTool",
+        "Failed to determine CWL specification version."
+    )]
+    #[case(
+        "cwlVersion: draft-3\nclass: CommandLineTool",
+        "Unsupported CWL version: draft-3"
+    )]
+    #[case("cwlVersion: v1.2", "Failed to determine CWL document class.")]
+    #[case(
+        "cwlVersion: v1.2\nclass: ExpressionTool",
+        "Unsupported CWL document class: ExpressionTool"
+    )]
+    #[case(
+        "cwlVersion: v1.2\nclass: CommandLineTool\ninputs: 1",
+        "invalid type: integer `1`, expected a sequence"
+    )]
+    #[case(
+        "cwlVersion: [v1.2",
+        "did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 13"
+    )]
+    fn test_cwlschema_from_str_error_messages(#[case] yaml: &str, #[case] expected: &str) {
+        let error = yaml.parse::<CwlSchema>().unwrap_err();
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "cwlVersion: draft-3\nclass: CommandLineTool",
+        "Unsupported CWL version: draft-3"
+    )]
+    #[case(
+        "cwlVersion: [v1.2",
+        "Failed to parse CWL schema from string: did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 13"
+    )]
+    fn test_cwlschema_from_string_error_messages(#[case] yaml: &str, #[case] expected: &str) {
+        let error = CwlSchema::from_string(yaml).unwrap_err();
+        assert_eq!(error.to_string(), expected);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_clt_roundtrip() {
+        crate::arbitrary_helpers::check_arbitrary(256, |clt: CommandLineTool| {
+            let yaml = serde_yaml::to_string(&clt).unwrap();
+            let parsed: CommandLineTool = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(
+                serde_yaml::to_value(&clt).unwrap(),
+                serde_yaml::to_value(&parsed).unwrap(),
+                "{yaml}"
+            );
+        });
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_wf_roundtrip() {
+        crate::arbitrary_helpers::check_arbitrary(256, |wf: Workflow| {
+            let yaml = serde_yaml::to_string(&wf).unwrap();
+            let parsed: Workflow = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(
+                serde_yaml::to_value(&wf).unwrap(),
+                serde_yaml::to_value(&parsed).unwrap(),
+                "{yaml}"
+            );
+            crate::graph::dag::WorkflowGraph::from_workflow(&parsed);
+        });
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_clt_validate() {
+        crate::arbitrary_helpers::check_arbitrary(256, |clt: CommandLineTool| {
+            let _ = CwlSchema::CommandLineTool(clt).validate();
+        });
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_wf_validate() {
+        crate::arbitrary_helpers::check_arbitrary(256, |wf: Workflow| {
+            let _ = CwlSchema::Workflow(wf).validate();
+        });
+    }
+
+    #[test]
+    fn test_cwlschema_from_path_io_error() {
+        let error = CwlSchema::from_path("test_data/cwl/missing.yml").unwrap_err();
+        assert!(
+            matches!(error, CwlError::Io { ref path, .. } if path == "test_data/cwl/missing.yml")
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Failed to open file 'test_data/cwl/missing.yml': "));
+    }
+
+    #[test]
+    fn test_resolve_schema_def_type() {
+        let yaml = r##"
+cwlVersion: v1.2
+class: CommandLineTool
+id: tool
+inputs:
+  - id: tumor
+    type: "#sample"
+  - id: normal
+    type: types.yml#sample
+outputs: []
+requirements:
+  - class: SchemaDefRequirement
+    types:
+      - name: "#sample"
+        type: record
+        fields:
+          - name: id
+            type: string
+          - name: reads
+            type: File
+"##;
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::CommandLineTool(ref tool) = schema else {
+            panic!("Expected CommandLineTool");
+        };
+        for (_, input) in tool.inputs() {
+            let def = schema.resolve_type(&input.r#type.to_string()).unwrap();
+            let CwlSchemaType::Record(record) = &def.r#type else {
+                panic!("Expected record type");
+            };
+            let fields = record.fields.fields();
+            assert_eq!(fields[1].name, "reads");
+            assert_eq!(fields[1].r#type.to_string(), "File");
+        }
+        assert!(schema.resolve_type("#missing").is_none());
+
+        let written: CwlSchema = schema.to_string().unwrap().parse().unwrap();
+        assert!(written.resolve_type("#sample").is_some());
+    }
+
+    #[test]
+    fn test_resolve_map_form_schema_def_types() {
+        let schema = CwlSchema::from_path("test_data/cwl/clt-schemadef-schema.yml").unwrap();
+        let CwlSchemaType::Record(sample) = &schema.resolve_type("#sample").unwrap().r#type else {
+            panic!("Expected record type");
+        };
+        let fields: Vec<String> = sample
+            .fields
+            .fields()
+            .iter()
+            .map(|field| format!("{}: {}", field.name, field.r#type))
+            .collect();
+        assert_eq!(fields, ["id: string", "reads: File"]);
+        assert!(matches!(
+            &schema.resolve_type("#mode").unwrap().r#type,
+            CwlSchemaType::Enum(r#enum) if r#enum.symbols == ["germline", "somatic"]
+        ));
+        assert_eq!(
+            schema.resolve_type("#regions").unwrap().r#type.to_string(),
+            "string[]"
+        );
+
+        let values = CwlValues::from_string(
+            "tumor: {id: t1, reads: {class: File, location: t1.fq}}\n\
+             normal: {id: n1}\nmode: somatic\nregions: [chr1, 2]",
+        )
+        .unwrap();
+        let errors: Vec<String> = values
+            .validate(&schema)
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "normal.reads: Missing value for required field",
+                "regions[1]: Expected string, got int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_run_path_cycle() {
+        let error = CwlSchema::from_path("test_data/cwl/runs/cycle-a.yml").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to load 'test_data/cwl/runs/cycle-b.yml' run by step 'b': \
+             Failed to load 'test_data/cwl/runs/cycle-a.yml' run by step 'a': \
+             Circular reference between documents: test_data/cwl/runs/cycle-a.yml -> \
+             test_data/cwl/runs/cycle-b.yml -> test_data/cwl/runs/cycle-a.yml"
+        );
+    }
+
+    #[test]
+    fn test_step_run_unknown_class() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\ninputs: []\noutputs: []\nrequirements: []\n\
+                    steps:\n- id: step\n  in: []\n  out: []\n  run:\n    class: ExpressionTool\n";
+        let error = yaml.parse::<CwlSchema>().unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unsupported CWL document class: ExpressionTool"));
+    }
+
+    #[rstest]
+    #[case("test_data/cwl/v1.0/wf-schema.yml", "v1.0")]
+    #[case("test_data/cwl/v1.1/clt-schema.yml", "v1.1")]
+    fn test_older_versions(#[case] path: &str, #[case] version: &str) {
+        let schema = CwlSchema::from_path(path).unwrap();
+        assert!(schema
+            .to_string()
+            .unwrap()
+            .starts_with(&format!("cwlVersion: {version}\n")));
+
+        let mut upgraded = schema.clone();
+        upgraded.upgrade();
+        let upgraded: CwlSchema = upgraded.to_string().unwrap().parse().unwrap();
+        let tool = match &upgraded {
+            CwlSchema::CommandLineTool(tool) => tool,
+            CwlSchema::Workflow(workflow) => {
+                assert!(workflow.validate_ids().is_empty());
+                assert!(workflow.validate_requirements().is_empty());
+                assert_eq!(
+                    workflow.effective_load_listing("reads"),
+                    LoadListing::DeepListing
+                );
+                workflow.steps[0].run.as_tool().unwrap()
+            }
+            CwlSchema::Operation(_) => panic!("Expected CommandLineTool or Workflow"),
+        };
+        assert_eq!(tool.cwl_version, MINIMAL_CWL_VERSION);
+        assert!(tool.validate_ids().is_empty());
+        assert_eq!(tool.needs_network(), Some(true));
+    }
+
+    #[test]
+    fn test_validate_collects_all_problems() {
+        let schema = CwlSchema::from_path("test_data/cwl/wf-invalid-schema.yml").unwrap();
+        let problems = schema.validate().unwrap_err();
+        assert_eq!(
+            problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "inputs[1].id: Duplicate id 'reads'",
+                "steps[0].in[1].valueFrom: JavaScript expression requires InlineJavascriptRequirement",
+                "steps[0].run.requirements[0]: DockerRequirement requires one of dockerPull, \
+                 dockerLoad, dockerFile, dockerImport or dockerImageId",
+                "steps[0].run.outputs[0].outputBinding.glob: JavaScript expression requires \
+                 InlineJavascriptRequirement",
+                "steps[0].in[0].source: Unknown source 'sample'",
+                "steps[0].in[2]: warning: Input 'extra' is not declared by the run process",
+                "steps[0].scatter: Unknown scatter input 'read'",
+                "outputs[0].outputSource: Unknown source 'align/report'",
+                "inputs[2].default: Expected int, got string",
+            ]
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .filter(|problem| problem.severity == Severity::Warning)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_problems_returns_warnings() {
+        let schema: CwlSchema = "cwlVersion: v1.2\nclass: Workflow\ninputs:\n  reads: File\n\
+             outputs: []\nrequirements: []\nsteps:\n  count:\n    in:\n      reads: reads\n\
+             \x20     extra: reads\n    out: []\n    run:\n      class: CommandLineTool\n      inputs:\n\
+             \x20       reads: File\n      outputs: []\n"
+            .parse()
+            .unwrap();
+        assert!(schema.validate().is_ok());
+        assert_eq!(
+            schema.problems(),
+            [ValidationError {
+                severity: Severity::Warning,
+                path: "steps[0].in[1]".to_string(),
+                message: "Input 'extra' is not declared by the run process".to_string(),
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case("source: reads", "outputSource: count/total", true)]
+    #[case("source: \"#reads\"", "outputSource: \"#count/total\"", true)]
+    #[case("source: \"#sample\"", "outputSource: \"#count/total\"", false)]
+    #[case("source: \"#reads\"", "outputSource: \"#count/lines\"", false)]
+    fn test_validate_fragment_references(
+        #[case] source: &str,
+        #[case] output_source: &str,
+        #[case] valid: bool,
+    ) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: Workflow\ninputs:\n  reads: File\nrequirements: []\n\
+             outputs:\n  total:\n    type: int\n    {output_source}\nsteps:\n  count:\n\
+             \x20   in:\n      reads:\n        {source}\n    out: [total]\n\
+             \x20   scatter: \"#reads\"\n    run:\n      class: CommandLineTool\n\
+             \x20     inputs:\n        reads: File\n      outputs:\n        total: int\n"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        assert_eq!(schema.validate().is_ok(), valid);
+    }
+
+    #[rstest]
+    #[case("arguments: [$(inputs.reads.path)]", true)]
+    #[case("arguments: [$(inputs.threads * 2)]", false)]
+    #[case("stdout: $(inputs.reads.nameroot).txt", true)]
+    #[case("stdout: ${return 'out.txt';}", false)]
+    #[case(
+        "requirements: [{class: InlineJavascriptRequirement}]\nstdout: ${return 'out.txt';}",
+        true
+    )]
+    fn test_validate_tool_expressions(#[case] field: &str, #[case] valid: bool) {
+        let yaml = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\ninputs:\n  reads: File\n  threads: int\n\
+             outputs: []\n{field}\n"
+        );
+        let schema: CwlSchema = yaml.parse().unwrap();
+        assert_eq!(schema.validate().is_ok(), valid);
+    }
+
+    #[test]
+    fn test_extensions_round_trip() {
+        let schema = CwlSchema::from_path("test_data/cwl/wf-extensions-schema.yml").unwrap();
+        let CwlSchema::Workflow(workflow) = &schema else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(workflow.extensions["s:license"], Value::from("Apache-2.0"));
+        assert!(workflow.extensions.contains_key("$namespaces"));
+        assert_eq!(
+            workflow.inputs[0].extensions["zefiro:sensitive"],
+            Value::from(true)
+        );
+        assert_eq!(
+            workflow.outputs[0].extensions["zefiro:retention"],
+            Value::from("30d")
+        );
+        let step = workflow.step("summarize").unwrap();
+        assert_eq!(step.extensions["zefiro:queue"], Value::from("gpu"));
+        let tool = step.run.as_tool().unwrap();
+        assert_eq!(tool.extensions["s:softwareVersion"], Value::from("2.1.0"));
+        assert_eq!(
+            tool.inputs[0].extensions["zefiro:cache"],
+            Value::from(false)
+        );
+        assert_eq!(
+            tool.outputs[0].extensions["zefiro:mime"],
+            Value::from("text/html")
+        );
+
+        let written: CwlSchema = schema.to_string().unwrap().parse().unwrap();
+        assert_eq!(
+            serde_yaml::to_value(&written).unwrap(),
+            serde_yaml::to_value(&schema).unwrap()
+        );
+    }
 }
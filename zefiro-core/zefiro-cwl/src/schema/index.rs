@@ -0,0 +1,277 @@
+use crate::error::{Severity, ValidationError};
+use serde::{de::DeserializeOwned, de::Error as _, Deserialize, Deserializer};
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::{PoisonError, RwLock};
+
+/// Lazily built map from ids to positions of items in a parameter list.
+///
+/// The index is built on the first lookup and rebuilt when it is stale: the length of the
+/// list changed or an item has another id than the indexed one, e.g. an item was added,
+/// renamed or replaced after the first lookup. A hit is checked against the item at the
+/// indexed position, a miss compares the ids of all items with the indexed ones, which
+/// doesn't allocate or take the write lock.
+#[derive(Debug, Default)]
+pub(crate) struct IdIndex(RwLock<Option<Positions>>);
+
+#[derive(Debug)]
+struct Positions {
+    /// Ids of the items in the order they were indexed.
+    order: Vec<Option<String>>,
+    ids: HashMap<String, usize>,
+}
+
+/// Result of a lookup in the current index.
+enum Lookup<'a, T> {
+    Found(&'a T),
+    Absent,
+    Stale,
+}
+
+impl Clone for IdIndex {
+    /// Clones are indexed on their first lookup.
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl IdIndex {
+    pub(crate) fn get<'a, T>(
+        &self,
+        items: &'a [T],
+        id: &str,
+        item_id: impl Fn(&T) -> Option<&str>,
+    ) -> Option<&'a T> {
+        match self.lookup(items, id, &item_id) {
+            Lookup::Found(item) => return Some(item),
+            Lookup::Absent => return None,
+            Lookup::Stale => {}
+        }
+        let order: Vec<Option<String>> = items
+            .iter()
+            .map(|item| item_id(item).map(str::to_string))
+            .collect();
+        let mut ids = HashMap::with_capacity(items.len());
+        for (position, id) in order.iter().enumerate() {
+            if let Some(id) = id {
+                ids.entry(id.clone()).or_insert(position);
+            }
+        }
+        let found = ids.get(id).map(|&position| &items[position]);
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Some(Positions { order, ids });
+        found
+    }
+
+    /// Looks up the `id` in the index, which is stale if it isn't built, was built for a list
+    /// of another length or any item has another id than the indexed one.
+    fn lookup<'a, T>(
+        &self,
+        items: &'a [T],
+        id: &str,
+        item_id: &impl Fn(&T) -> Option<&str>,
+    ) -> Lookup<'a, T> {
+        let index = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        let Some(positions) = index
+            .as_ref()
+            .filter(|positions| positions.order.len() == items.len())
+        else {
+            return Lookup::Stale;
+        };
+        let Some(&position) = positions.ids.get(id) else {
+            let stale = positions
+                .order
+                .iter()
+                .zip(items)
+                .any(|(indexed, item)| indexed.as_deref() != item_id(item));
+            return if stale { Lookup::Stale } else { Lookup::Absent };
+        };
+        match items.get(position) {
+            Some(item) if item_id(item) == Some(id) => Lookup::Found(item),
+            _ => Lookup::Stale,
+        }
+    }
+}
+
+/// Collects errors for missing and duplicated ids, `kind` is used as the path prefix.
+pub(crate) fn id_errors<'a>(
+    kind: &str,
+    ids: impl Iterator<Item = Option<&'a str>>,
+) -> Vec<ValidationError> {
+    let mut seen = HashSet::new();
+    let mut errors = vec![];
+    for (position, id) in ids.enumerate() {
+        let message = match id {
+            None | Some("") => "Missing id".to_string(),
+            Some(id) if !seen.insert(id) => format!("Duplicate id '{id}'"),
+            Some(_) => continue,
+        };
+        errors.push(ValidationError {
+            severity: Severity::Error,
+            path: format!("{kind}[{position}].id"),
+            message,
+        });
+    }
+    errors
+}
+
+/// Deserializes parameters given as a list or as a map from ids to parameters,
+/// e.g. `inputs: {in_file: {type: File}, threads: int}`.
+pub(crate) fn deserialize_parameters<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    // `type` is the map predicate of parameters
+    deserialize_map_form(deserializer, "id", Some("type"))
+}
+
+/// Deserializes step inputs given as a list or as a map from ids to step inputs,
+/// e.g. `in: {reads: fastq, threads: {default: 4}}`.
+pub(crate) fn deserialize_step_inputs<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    // `source` is the map predicate of step inputs
+    deserialize_map_form(deserializer, "id", Some("source"))
+}
+
+/// Deserializes workflow steps given as a list or as a map from ids to steps.
+pub(crate) fn deserialize_steps<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    deserialize_map_form(deserializer, "id", None)
+}
+
+/// Deserializes a list of items given as a list or as a map, the map keys are set as the
+/// `subject` field of the items and an item that is not a mapping is the value of the
+/// `predicate` field.
+/// See: https://www.commonwl.org/v1.2/SchemaSalad.html#Identifier_maps
+pub(crate) fn deserialize_map_form<'de, D, T>(
+    deserializer: D,
+    subject: &str,
+    predicate: Option<&str>,
+) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let items = match Value::deserialize(deserializer)? {
+        Value::Mapping(items) => items,
+        items => return serde_yaml::from_value(items).map_err(D::Error::custom),
+    };
+    items
+        .into_iter()
+        .map(|(key, item)| {
+            let mut item = match (item, predicate) {
+                (Value::Mapping(item), _) => item,
+                (Value::Null, None) => Mapping::new(),
+                (value, Some(predicate)) => [(predicate.into(), value)].into_iter().collect(),
+                (_, None) => {
+                    return Err(D::Error::custom(format!(
+                        "expected a mapping for '{}'",
+                        key.as_str().unwrap_or_default()
+                    )))
+                }
+            };
+            item.insert(subject.into(), key);
+            serde_yaml::from_value(Value::Mapping(item)).map_err(D::Error::custom)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use rstest::rstest;
+
+    fn id<'a>(item: &'a (&str, u8)) -> Option<&'a str> {
+        Some(item.0)
+    }
+
+    #[test]
+    fn test_id_index_get() {
+        let index = IdIndex::default();
+        let items = vec![("a", 1), ("b", 2)];
+        assert_eq!(index.get(&items, "b", id), Some(&("b", 2)));
+        assert_eq!(index.get(&items, "c", id), None);
+
+        let items = vec![("b", 3), ("c", 4)];
+        assert_eq!(index.get(&items, "b", id), Some(&("b", 3)));
+        assert_eq!(index.get(&items, "c", id), Some(&("c", 4)));
+    }
+
+    #[test]
+    fn test_id_index_rebuild() {
+        let index = IdIndex::default();
+        let mut items = vec![("a", 1), ("b", 2)];
+        assert_eq!(index.get(&items, "a", id), Some(&("a", 1)));
+
+        assert_eq!(index.get(&items, "c", id), None);
+
+        items.push(("c", 3));
+        assert_eq!(index.get(&items, "c", id), Some(&("c", 3)));
+
+        items[0].0 = "x";
+        assert_eq!(index.get(&items, "x", id), Some(&("x", 1)));
+        assert_eq!(index.get(&items, "a", id), None);
+
+        items[1] = ("y", 5);
+        assert_eq!(index.get(&items, "y", id), Some(&("y", 5)));
+        assert_eq!(index.get(&items, "b", id), None);
+    }
+
+    #[test]
+    fn test_id_errors() {
+        let errors = id_errors("inputs", [Some("a"), None, Some("a")].into_iter());
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["inputs[1].id: Missing id", "inputs[2].id: Duplicate id 'a'"]
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "cwlVersion: v1.2\nclass: CommandLineTool\nbaseCommand: cat\n\
+         inputs:\n  in_file:\n    type: File\n    inputBinding:\n      position: 1\n  threads: int?\n\
+         outputs:\n  out_file: stdout\n",
+        "cwlVersion: v1.2\nclass: CommandLineTool\nbaseCommand: cat\n\
+         inputs:\n- id: in_file\n  type: File\n  inputBinding:\n    position: 1\n- id: threads\n  type: int?\n\
+         outputs:\n- id: out_file\n  type: stdout\n"
+    )]
+    #[case(
+        "cwlVersion: v1.2\nclass: Workflow\nrequirements: []\n\
+         inputs:\n  message: string\n\
+         outputs:\n  out:\n    type: File\n    outputSource: echo/out\n\
+         steps:\n  echo:\n    in: [{id: message, source: message}]\n    out: [{id: out}]\n\
+         \x20   run:\n      class: Operation\n      inputs:\n        message: string\n      outputs:\n        out: File\n",
+        "cwlVersion: v1.2\nclass: Workflow\nrequirements: []\n\
+         inputs:\n- id: message\n  type: string\n\
+         outputs:\n- id: out\n  type: File\n  outputSource: echo/out\n\
+         steps:\n- id: echo\n  in: [{id: message, source: message}]\n  out: [{id: out}]\n\
+         \x20 run:\n    class: Operation\n    inputs:\n    - id: message\n      type: string\n\
+         \x20   outputs:\n    - id: out\n      type: File\n"
+    )]
+    fn test_map_form_parameters(#[case] map_form: &str, #[case] list_form: &str) {
+        let map_form: CwlSchema = map_form.parse().unwrap();
+        let list_form: CwlSchema = list_form.parse().unwrap();
+        assert_eq!(
+            map_form.to_string().unwrap(),
+            list_form.to_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_map_form_steps_error() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\nrequirements: []\ninputs: []\noutputs: []\n\
+                    steps:\n  echo: tools/echo.cwl\n";
+        let error = yaml.parse::<CwlSchema>().unwrap_err();
+        assert!(
+            error.to_string().contains("expected a mapping for 'echo'"),
+            "{error}"
+        );
+    }
+}
@@ -1,5 +1,9 @@
 pub mod command_line_tool;
 pub mod document;
+mod index;
+pub mod operation;
+pub mod packed;
+pub mod preprocess;
 pub mod requirements;
 pub mod types;
 pub mod workflow;
@@ -0,0 +1,154 @@
+use crate::error::ValidationError;
+use crate::schema::index::{deserialize_parameters, id_errors, IdIndex};
+use crate::schema::requirements::{
+    deserialize_requirements, CommandLineToolRequirement, MINIMAL_CWL_VERSION,
+};
+use crate::schema::types::{
+    Any, CwlSchemaType, CwlTypeDef, Documentation, Format, LoadListing, OPERATION_CWL_CLASS,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Abstract process that declares its inputs and outputs without an implementation,
+/// it can be drawn and validated as a part of a workflow, but can't be executed.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#Operation
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    #[serde(default = "Operation::default_cwl_version")]
+    pub cwl_version: String,
+    #[serde(default = "Operation::default_class")]
+    pub class: String,
+    pub doc: Option<Documentation>,
+    #[serde(default)]
+    pub id: String,
+    pub label: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_parameters")]
+    pub inputs: Vec<OperationInputParameter>,
+    #[serde(default, deserialize_with = "deserialize_parameters")]
+    pub outputs: Vec<OperationOutputParameter>,
+    #[serde(default, deserialize_with = "deserialize_requirements")]
+    pub requirements: Vec<CommandLineToolRequirement>,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    input_index: IdIndex,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    output_index: IdIndex,
+}
+
+impl Operation {
+    fn default_cwl_version() -> String {
+        MINIMAL_CWL_VERSION.to_string()
+    }
+
+    fn default_class() -> String {
+        OPERATION_CWL_CLASS.to_string()
+    }
+
+    /// Upgrades the operation to `MINIMAL_CWL_VERSION`, see `CwlSchema::upgrade`.
+    pub fn upgrade(&mut self) {
+        self.cwl_version = MINIMAL_CWL_VERSION.to_string();
+    }
+
+    /// Returns input parameter with the given `id`.
+    pub fn input(&self, id: &str) -> Option<&OperationInputParameter> {
+        self.input_index
+            .get(&self.inputs, id, |input| Some(input.id.as_str()))
+    }
+
+    /// Returns output parameter with the given `id`.
+    pub fn output(&self, id: &str) -> Option<&OperationOutputParameter> {
+        self.output_index
+            .get(&self.outputs, id, |output| Some(output.id.as_str()))
+    }
+
+    /// Iterates over `(id, input)` pairs in the declaration order.
+    pub fn inputs(&self) -> impl Iterator<Item = (&str, &OperationInputParameter)> {
+        self.inputs.iter().map(|input| (input.id.as_str(), input))
+    }
+
+    /// Iterates over `(id, output)` pairs in the declaration order.
+    pub fn outputs(&self) -> impl Iterator<Item = (&str, &OperationOutputParameter)> {
+        self.outputs
+            .iter()
+            .map(|output| (output.id.as_str(), output))
+    }
+
+    /// Iterates over types declared in `SchemaDefRequirement`s of the operation.
+    pub fn type_defs(&self) -> impl Iterator<Item = &CwlTypeDef> {
+        self.requirements
+            .iter()
+            .filter_map(|requirement| match requirement {
+                CommandLineToolRequirement::SchemaDefRequirement(schema_def) => {
+                    Some(&schema_def.types)
+                }
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Checks that all inputs and outputs have unique ids, lookups by id rely on it.
+    pub fn validate_ids(&self) -> Vec<ValidationError> {
+        let mut errors = id_errors("inputs", self.inputs.iter().map(|i| Some(i.id.as_str())));
+        errors.extend(id_errors(
+            "outputs",
+            self.outputs.iter().map(|o| Some(o.id.as_str())),
+        ));
+        errors
+    }
+}
+
+/// Represents an input parameter for an `Operation`.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#OperationInputParameter
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct OperationInputParameter {
+    pub id: String,
+    pub r#type: CwlSchemaType,
+    pub label: Option<String>,
+    pub doc: Option<Documentation>,
+    pub default: Option<Any>,
+    pub format: Option<Format>,
+    pub streamable: Option<bool>,
+    pub load_listing: Option<LoadListing>,
+}
+
+/// Represents an output parameter for an `Operation`.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#OperationOutputParameter
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct OperationOutputParameter {
+    pub id: String,
+    pub r#type: CwlSchemaType,
+    pub label: Option<String>,
+    pub doc: Option<Documentation>,
+    pub format: Option<Format>,
+    pub streamable: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::document::CwlSchema;
+
+    #[test]
+    fn test_operation() {
+        let yaml = "cwlVersion: v1.2\nclass: Operation\nid: align\n\
+                    inputs:\n- id: reads\n  type: File\n  format: edam:format_1930\n\
+                    outputs:\n- id: bam\n  type: File\n\
+                    requirements: []\n";
+        let schema: CwlSchema = yaml.parse().unwrap();
+        let CwlSchema::Operation(ref operation) = schema else {
+            panic!("Expected Operation");
+        };
+        assert!(operation.input("reads").unwrap().format.is_some());
+        assert_eq!(operation.output("bam").unwrap().r#type.to_string(), "File");
+        assert_eq!(schema.to_string().unwrap(), yaml);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::error::{CwlError, Severity, ValidationError};
+use crate::schema::document::CwlSchema;
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// Id of the entry point of a packed document.
+pub const PACKED_MAIN_ID: &str = "main";
+
+/// Represents a packed document (e.g. produced by `cwltool --pack`) with all processes
+/// listed in the top-level `$graph` array.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#Packed_documents
+#[derive(Clone, Debug)]
+pub struct PackedDocument {
+    /// Processes in the order of `$graph`, step `run` references are replaced by the processes.
+    pub documents: Vec<CwlSchema>,
+}
+
+impl PackedDocument {
+    /// Returns process with the given `id`, the leading `#` is optional.
+    pub fn document(&self, id: &str) -> Option<&CwlSchema> {
+        let id = id.trim_start_matches('#');
+        self.documents
+            .iter()
+            .find(|document| document.id().trim_start_matches('#') == id)
+    }
+
+    /// Returns the entry point of the packed document, i.e. the process with id `#main`.
+    pub fn main(&self) -> Option<&CwlSchema> {
+        self.document(PACKED_MAIN_ID)
+    }
+}
+
+impl CwlSchema {
+    /// Deserializes a packed YAML Value with the `$graph` array of processes.
+    ///
+    /// `cwlVersion` of the root applies to all processes, `run: "#id"` of workflow steps
+    /// is resolved to the process with that id.
+    ///
+    /// ```
+    /// use zefiro_cwl::CwlSchema;
+    ///
+    /// let file = std::fs::File::open("test_data/cwl/wf-packed-schema.yml").unwrap();
+    /// let packed = CwlSchema::from_packed(serde_yaml::from_reader(file).unwrap()).unwrap();
+    /// assert!(matches!(packed.main(), Some(CwlSchema::Workflow(_))));
+    /// ```
+    pub fn from_packed(value: Value) -> Result<PackedDocument, CwlError> {
+        let version = value
+            .get("cwlVersion")
+            .cloned()
+            .ok_or(CwlError::MissingVersion)?;
+        let Some(Value::Sequence(graph)) = value.get("$graph") else {
+            return Err(CwlError::Validation(vec![ValidationError {
+                severity: Severity::Error,
+                path: "$graph".to_string(),
+                message: "Packed document must have a $graph array".to_string(),
+            }]));
+        };
+        let ids: HashMap<&str, usize> = graph
+            .iter()
+            .enumerate()
+            .filter_map(|(position, document)| {
+                let id = document.get("id").and_then(Value::as_str)?;
+                Some((id.trim_start_matches('#'), position))
+            })
+            .collect();
+
+        let mut errors = vec![];
+        let mut resolved = Vec::with_capacity(graph.len());
+        for (position, document) in graph.iter().enumerate() {
+            let mut document = document.clone();
+            if let Value::Mapping(mapping) = &mut document {
+                mapping
+                    .entry("cwlVersion".into())
+                    .or_insert_with(|| version.clone());
+            }
+            let path = format!("$graph[{position}]");
+            resolve_runs(
+                &mut document,
+                graph,
+                &ids,
+                &mut vec![position],
+                &path,
+                &mut errors,
+            );
+            resolved.push(document);
+        }
+        if !errors.is_empty() {
+            return Err(CwlError::Validation(errors));
+        }
+
+        Ok(PackedDocument {
+            documents: resolved
+                .into_iter()
+                .map(Self::from_yaml)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Replaces `run` references of the workflow steps with copies of the referenced processes,
+/// `stack` holds positions of the processes being resolved to detect circular references.
+fn resolve_runs(
+    document: &mut Value,
+    graph: &[Value],
+    ids: &HashMap<&str, usize>,
+    stack: &mut Vec<usize>,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let steps: Vec<&mut Value> = match document.get_mut("steps") {
+        Some(Value::Sequence(steps)) => steps.iter_mut().collect(),
+        Some(Value::Mapping(steps)) => steps.values_mut().collect(),
+        _ => return,
+    };
+    for (position, step) in steps.into_iter().enumerate() {
+        let path = format!("{path}.steps[{position}].run");
+        let Some(run) = step.get_mut("run") else {
+            continue;
+        };
+        let Value::String(reference) = run else {
+            resolve_runs(run, graph, ids, stack, &path, errors);
+            continue;
+        };
+        let reference = reference.clone();
+        let Some(&target) = ids.get(reference.trim_start_matches('#')) else {
+            errors.push(ValidationError {
+                severity: Severity::Error,
+                path,
+                message: format!("Unknown process '{reference}'"),
+            });
+            continue;
+        };
+        if stack.contains(&target) {
+            errors.push(ValidationError {
+                severity: Severity::Error,
+                path,
+                message: format!("Circular reference to process '{reference}'"),
+            });
+            continue;
+        }
+
+        let mut process = graph[target].clone();
+        stack.push(target);
+        resolve_runs(&mut process, graph, ids, stack, &path, errors);
+        stack.pop();
+        *run = process;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::workflow::StepRun;
+    use rstest::rstest;
+
+    #[test]
+    fn test_from_packed() {
+        let file = std::fs::File::open("test_data/cwl/wf-packed-schema.yml").unwrap();
+        let packed = CwlSchema::from_packed(serde_yaml::from_reader(file).unwrap()).unwrap();
+        assert_eq!(packed.documents.len(), 3);
+        assert!(matches!(
+            packed.document("report"),
+            Some(CwlSchema::CommandLineTool(_))
+        ));
+
+        let Some(CwlSchema::Workflow(main)) = packed.main() else {
+            panic!("Expected main Workflow");
+        };
+        assert_eq!(main.cwl_version, "v1.2");
+        let align = main.step("align").unwrap().run.as_tool().unwrap();
+        assert_eq!(
+            align.base_command.as_ref().unwrap().parts(),
+            vec!["bwa", "mem"]
+        );
+        let StepRun::CommandLineTool(report) = &main.step("report").unwrap().run else {
+            panic!("Expected CommandLineTool");
+        };
+        // Stream outputs of the referenced tools are normalized as well
+        assert_eq!(report.output("stats").unwrap().r#type.to_string(), "File");
+    }
+
+    #[rstest]
+    #[case(
+        "cwlVersion: v1.2\n$graph:\n- class: Workflow\n  id: main\n  inputs: []\n  outputs: []\n\
+         \x20 steps:\n  - id: step\n    in: []\n    out: []\n    run: '#missing'\n",
+        "$graph[0].steps[0].run: Unknown process '#missing'"
+    )]
+    #[case(
+        "cwlVersion: v1.2\n$graph:\n- class: Workflow\n  id: main\n  inputs: []\n  outputs: []\n\
+         \x20 steps:\n  - id: step\n    in: []\n    out: []\n    run: '#main'\n",
+        "$graph[0].steps[0].run: Circular reference to process '#main'"
+    )]
+    #[case(
+        "cwlVersion: v1.2\nclass: Workflow\n",
+        "$graph: Packed document must have a $graph array"
+    )]
+    fn test_from_packed_errors(#[case] yaml: &str, #[case] expected: &str) {
+        let error = CwlSchema::from_packed(serde_yaml::from_str(yaml).unwrap()).unwrap_err();
+        let CwlError::Validation(errors) = error else {
+            panic!("Expected Validation error, got: {error}");
+        };
+        assert_eq!(errors[0].to_string(), expected);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::error::CwlError;
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+
+/// Directive that is replaced by the parsed YAML document it refers to.
+pub const IMPORT_DIRECTIVE: &str = "$import";
+/// Directive that is replaced by the raw text of the file it refers to.
+pub const INCLUDE_DIRECTIVE: &str = "$include";
+
+/// Resolves `$import` and `$include` directives of a YAML document before it's deserialized.
+/// See: https://www.commonwl.org/v1.2/SchemaSalad.html#Import
+#[derive(Clone, Debug, Default)]
+pub struct Preprocessor {
+    base_dir: PathBuf,
+}
+
+impl Preprocessor {
+    /// Creates a preprocessor that resolves directives of the top-level document
+    /// relative to `base_dir`.
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+
+    /// Replaces directives of the `value` with the referenced documents, directives of the
+    /// imported documents are resolved relative to the importing file.
+    ///
+    /// ```
+    /// use zefiro_cwl::schema::preprocess::Preprocessor;
+    ///
+    /// let value = serde_yaml::from_str("requirements: {$import: common/workflow-requirements.yml}").unwrap();
+    /// let value = Preprocessor::new("test_data/cwl/imports").resolve(value).unwrap();
+    /// assert!(value["requirements"].is_sequence());
+    /// ```
+    pub fn resolve(&self, value: Value) -> Result<Value, CwlError> {
+        resolve_value(value, &self.base_dir, &mut vec![])
+    }
+}
+
+/// Canonical and displayed paths of the documents being loaded, used to detect cycles.
+pub(crate) type DocumentStack = Vec<(PathBuf, String)>;
+
+/// Pushes `path` onto the `stack` of documents being loaded, fails if it's already loading.
+pub(crate) fn enter_document(path: &Path, stack: &mut DocumentStack) -> Result<(), CwlError> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|source| io_error(path, source))?;
+    let display = path.display().to_string();
+    if let Some(start) = stack.iter().position(|(seen, _)| *seen == canonical) {
+        let mut cycle: Vec<String> = stack[start..].iter().map(|(_, d)| d.clone()).collect();
+        cycle.push(display);
+        return Err(CwlError::CircularReference(cycle));
+    }
+    stack.push((canonical, display));
+    Ok(())
+}
+
+/// Resolves directives of a document located in `dir`.
+pub(crate) fn resolve_directives(
+    value: Value,
+    dir: &Path,
+    stack: &mut DocumentStack,
+) -> Result<Value, CwlError> {
+    resolve_value(value, dir, stack)
+}
+
+fn resolve_file(value: Value, path: &Path, stack: &mut DocumentStack) -> Result<Value, CwlError> {
+    enter_document(path, stack)?;
+    let value = resolve_value(value, path.parent().unwrap_or(Path::new("")), stack)?;
+    stack.pop();
+    Ok(value)
+}
+
+fn resolve_value(value: Value, dir: &Path, stack: &mut DocumentStack) -> Result<Value, CwlError> {
+    match value {
+        Value::Mapping(mapping) => {
+            if let Some(target) = directive(&mapping, IMPORT_DIRECTIVE) {
+                let path = dir.join(target);
+                let imported = serde_yaml::from_str(&read(&path)?).map_err(|e| {
+                    CwlError::yaml_with_context(
+                        format!("Failed to deserialize '{}'", path.display()),
+                        e,
+                    )
+                })?;
+                return resolve_file(imported, &path, stack);
+            }
+            if let Some(target) = directive(&mapping, INCLUDE_DIRECTIVE) {
+                return read(&dir.join(target)).map(Value::String);
+            }
+            mapping
+                .into_iter()
+                .map(|(key, value)| Ok((key, resolve_value(value, dir, stack)?)))
+                .collect::<Result<Mapping, _>>()
+                .map(Value::Mapping)
+        }
+        Value::Sequence(items) => items
+            .into_iter()
+            .map(|item| resolve_value(item, dir, stack))
+            .collect::<Result<_, _>>()
+            .map(Value::Sequence),
+        value => Ok(value),
+    }
+}
+
+/// Returns target of the `key` directive, directives are mappings with a single entry.
+fn directive<'a>(mapping: &'a Mapping, key: &str) -> Option<&'a str> {
+    if mapping.len() != 1 {
+        return None;
+    }
+    mapping.get(key).and_then(Value::as_str)
+}
+
+fn read(path: &Path) -> Result<String, CwlError> {
+    std::fs::read_to_string(path).map_err(|source| io_error(path, source))
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> CwlError {
+    CwlError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use crate::schema::requirements::{CommandLineToolRequirement, WorkflowRequirement};
+    use crate::schema::types::Documentation;
+
+    #[test]
+    fn test_nested_imports() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/imports/wf-import-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        assert!(matches!(
+            workflow.requirements[..],
+            [WorkflowRequirement::InlineJavascriptRequirement(_)]
+        ));
+
+        let tool = workflow.step("echo").unwrap().run.as_tool().unwrap();
+        assert!(matches!(
+            &tool.requirements[0],
+            CommandLineToolRequirement::DockerRequirement(docker) if docker.docker_pull.as_deref() == Some("debian:12.8")
+        ));
+        assert!(matches!(
+            &tool.doc,
+            Some(Documentation::SingleLine(doc)) if doc == "Prints the message into out.txt.\n"
+        ));
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let error = CwlSchema::from_path("test_data/cwl/imports/cycle-a.yml").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Circular reference between documents: test_data/cwl/imports/cycle-a.yml -> \
+             test_data/cwl/imports/cycle-b.yml -> test_data/cwl/imports/cycle-a.yml"
+        );
+    }
+
+    #[test]
+    fn test_missing_import() {
+        let value = serde_yaml::from_str("run: {$import: missing.yml}").unwrap();
+        let error = Preprocessor::new("test_data/cwl")
+            .resolve(value)
+            .unwrap_err();
+        assert!(matches!(error, CwlError::Io { path, .. } if path == "test_data/cwl/missing.yml"));
+    }
+}
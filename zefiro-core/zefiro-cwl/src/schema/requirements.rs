@@ -1,24 +1,75 @@
-use serde::{Deserialize, Serialize};
+use crate::schema::index::deserialize_map_form;
+use crate::schema::types::{CwlTypeDef, LoadListing};
+use serde::{de::DeserializeOwned, de::Error as _, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
+use serde_yaml::Value;
 
 pub const MINIMAL_CWL_VERSION: &str = "v1.2";
+pub const CWL_VERSION_1_0: &str = "v1.0";
+pub const CWL_VERSION_1_1: &str = "v1.1";
+/// Versions that documents can declare, older documents keep their semantics and can be
+/// upgraded to `MINIMAL_CWL_VERSION`.
+pub const SUPPORTED_CWL_VERSIONS: &[&str] =
+    &[CWL_VERSION_1_0, CWL_VERSION_1_1, MINIMAL_CWL_VERSION];
+
+/// Returns `loadListing` of `Directory` inputs that don't specify it, the listing was loaded
+/// recursively before v1.1.
+pub(crate) fn default_load_listing(cwl_version: &str) -> LoadListing {
+    if cwl_version == CWL_VERSION_1_0 {
+        LoadListing::DeepListing
+    } else {
+        LoadListing::default()
+    }
+}
+
+/// Extension hints of cwltool that are standard requirements since v1.1.
+/// See: https://www.commonwl.org/v1.1/CommandLineTool.html#Changelog
+const CWLTOOL_EXTENSION_CLASSES: &[(&str, &str)] = &[
+    ("cwltool:LoadListingRequirement", "LoadListingRequirement"),
+    ("cwltool:NetworkAccess", "NetworkAccess"),
+    ("cwltool:TimeLimit", "ToolTimeLimit"),
+    ("cwltool:WorkReuse", "WorkReuse"),
+];
 
 const CPU_NUM_DEFAULT: u32 = 1;
 const RAM_SIZE_IN_MB_DEFAULT: u32 = 1024;
 const TMPDIR_MIN_IN_MB_DEFAULT: u32 = 1024;
 const OUTDIR_MIN_IN_MB_DEFAULT: u32 = 1024;
 
-/// Describes requirements for `Workflow`.
+/// Describes requirements for `Workflow`, requirements of tools apply to all its steps.
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "class")]
 pub enum WorkflowRequirement {
     InlineJavascriptRequirement(InlineJavascriptRequirement),
     ScatterFeatureRequirement(ScatterFeatureRequirement),
+    SchemaDefRequirement(SchemaDefRequirement),
+    SubworkflowFeatureRequirement(SubworkflowFeatureRequirement),
+    MultipleInputFeatureRequirement(MultipleInputFeatureRequirement),
+    LoadListingRequirement(LoadListingRequirement),
+    DockerRequirement(DockerRequirement),
+    ResourceRequirement(ResourceRequirement),
+    ToolTimeLimit(ToolTimeLimit),
+    WorkReuse(WorkReuse),
+    NetworkAccess(NetworkAccess),
+    SoftwareRequirement(SoftwareRequirement),
+    ShellCommandRequirement(ShellCommandRequirement),
+}
+
+/// Deserializes requirements or hints given as a list or as a map from classes to
+/// requirements, e.g. `requirements: {DockerRequirement: {dockerPull: debian:12.8}}`.
+pub(crate) fn deserialize_requirements<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    deserialize_map_form(deserializer, "class", None)
 }
 
 /// Describes requirements for `CommandLineTool`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "class")]
 pub enum CommandLineToolRequirement {
     DockerRequirement(DockerRequirement),
@@ -26,21 +77,169 @@ pub enum CommandLineToolRequirement {
     InlineJavascriptRequirement(InlineJavascriptRequirement),
     ToolTimeLimit(ToolTimeLimit),
     WorkReuse(WorkReuse),
+    SchemaDefRequirement(SchemaDefRequirement),
+    NetworkAccess(NetworkAccess),
+    LoadListingRequirement(LoadListingRequirement),
+    SoftwareRequirement(SoftwareRequirement),
+    ShellCommandRequirement(ShellCommandRequirement),
+}
+
+/// Entry of `hints` that the runner may ignore, hints of unknown classes (e.g. vendor
+/// extensions) or with unexpected fields are kept as is. `R` is the requirement type of
+/// the process, `WorkflowRequirement` for workflows.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#Requirements_and_hints
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Hint<R = CommandLineToolRequirement> {
+    Requirement(R),
+    Unknown(Value),
+}
+
+impl<R> Hint<R> {
+    /// Returns the requirement if the hint class is known.
+    pub fn requirement(&self) -> Option<&R> {
+        match self {
+            Self::Requirement(requirement) => Some(requirement),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// Replaces cwltool extension hint with the standard requirement, e.g.
+    /// `cwltool:TimeLimit` with `ToolTimeLimit`, other hints are kept as is.
+    pub(crate) fn upgrade(&mut self)
+    where
+        R: DeserializeOwned,
+    {
+        let Self::Unknown(Value::Mapping(hint)) = self else {
+            return;
+        };
+        let Some((_, class)) = CWLTOOL_EXTENSION_CLASSES
+            .iter()
+            .find(|(extension, _)| hint.get("class").and_then(Value::as_str) == Some(extension))
+        else {
+            return;
+        };
+        let mut upgraded = hint.clone();
+        upgraded.insert("class".into(), (*class).into());
+        if let Ok(requirement) = serde_yaml::from_value(Value::Mapping(upgraded)) {
+            *self = Self::Requirement(requirement);
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, R: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for Hint<R> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        if u.arbitrary()? {
+            return Ok(Self::Requirement(u.arbitrary()?));
+        }
+        // Namespaced classes never match the known ones
+        let class = format!("ext:{}", u.arbitrary::<String>()?);
+        let value: String = u.arbitrary()?;
+        Ok(Self::Unknown(Value::Mapping(
+            [
+                ("class".into(), class.into()),
+                ("value".into(), value.into()),
+            ]
+            .into_iter()
+            .collect(),
+        )))
+    }
+}
+
+/// Requirement that can be looked up by its type in requirements and hints,
+/// e.g. `tool.effective_requirement::<DockerRequirement>()`.
+pub trait Requirement {
+    fn from_tool_requirement(requirement: &CommandLineToolRequirement) -> Option<&Self>;
+    fn from_workflow_requirement(requirement: &WorkflowRequirement) -> Option<&Self>;
+}
+
+macro_rules! impl_requirement {
+    ($name:ident, tool: $tool:tt, workflow: $workflow:tt) => {
+        impl Requirement for $name {
+            fn from_tool_requirement(requirement: &CommandLineToolRequirement) -> Option<&Self> {
+                impl_requirement!(@match requirement, CommandLineToolRequirement, $name, $tool)
+            }
+
+            fn from_workflow_requirement(requirement: &WorkflowRequirement) -> Option<&Self> {
+                impl_requirement!(@match requirement, WorkflowRequirement, $name, $workflow)
+            }
+        }
+    };
+    (@match $requirement:ident, $enum:ident, $name:ident, true) => {
+        match $requirement {
+            $enum::$name(requirement) => Some(requirement),
+            _ => None,
+        }
+    };
+    (@match $requirement:ident, $enum:ident, $name:ident, false) => {{
+        let _ = $requirement;
+        None
+    }};
 }
 
+impl_requirement!(DockerRequirement, tool: true, workflow: true);
+impl_requirement!(ResourceRequirement, tool: true, workflow: true);
+impl_requirement!(InlineJavascriptRequirement, tool: true, workflow: true);
+impl_requirement!(ToolTimeLimit, tool: true, workflow: true);
+impl_requirement!(WorkReuse, tool: true, workflow: true);
+impl_requirement!(SchemaDefRequirement, tool: true, workflow: true);
+impl_requirement!(NetworkAccess, tool: true, workflow: true);
+impl_requirement!(LoadListingRequirement, tool: true, workflow: true);
+impl_requirement!(SoftwareRequirement, tool: true, workflow: true);
+impl_requirement!(ShellCommandRequirement, tool: true, workflow: true);
+impl_requirement!(ScatterFeatureRequirement, tool: false, workflow: true);
+impl_requirement!(SubworkflowFeatureRequirement, tool: false, workflow: true);
+impl_requirement!(MultipleInputFeatureRequirement, tool: false, workflow: true);
+
 /// Specifies Docker container requirements.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#DockerRequirement
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct DockerRequirement {
-    pub docker_pull: String,
+    /// Image to pull with `docker pull`.
+    pub docker_pull: Option<String>,
+    /// HTTP URL of an image archive to load with `docker load`.
+    pub docker_load: Option<String>,
+    /// Dockerfile to build the image with `docker build`.
+    pub docker_file: Option<String>,
+    /// HTTP URL of an image archive to import with `docker import`.
+    pub docker_import: Option<String>,
+    /// Image id used for `docker run`, defaults to `dockerPull`.
+    pub docker_image_id: Option<String>,
+    /// Directory inside the container where the tool writes its outputs.
+    pub docker_output_directory: Option<String>,
+}
+
+impl DockerRequirement {
+    /// Whether any field specifies where the image comes from.
+    pub fn has_image_source(&self) -> bool {
+        [
+            &self.docker_pull,
+            &self.docker_load,
+            &self.docker_file,
+            &self.docker_import,
+            &self.docker_image_id,
+        ]
+        .iter()
+        .any(|source| source.is_some())
+    }
+
+    /// Returns the image reference to pull, `dockerPull` or else `dockerImageId`.
+    pub fn image(&self) -> Option<&str> {
+        self.docker_pull
+            .as_deref()
+            .or(self.docker_image_id.as_deref())
+    }
 }
 
 /// Specifies resource constraints for running the tool.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#ResourceRequirement
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceRequirement {
     #[serde(default = "ResourceRequirement::cores_min")]
@@ -75,17 +274,28 @@ impl ResourceRequirement {
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#InlineJavascriptRequirement
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct InlineJavascriptRequirement;
 
+/// Indicates that the command line is run by a shell, arguments are quoted unless their
+/// bindings set `shellQuote: false`.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#ShellCommandRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ShellCommandRequirement;
+
 /// Specifies an upper limit on the execution time of a `CommandLineTool` (in seconds).
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#ToolTimeLimit
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ToolTimeLimit {
     pub timelimit: Timelimit,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum Timelimit {
     Seconds(u32),
@@ -96,13 +306,218 @@ pub enum Timelimit {
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#ScatterFeatureRequirement
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ScatterFeatureRequirement;
 
+/// Indicates that the workflow platform must support multiple sources of a step input.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#MultipleInputFeatureRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MultipleInputFeatureRequirement;
+
+/// Indicates that the workflow platform must support nested workflows in the `run` field of `WorkflowStep`.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#SubworkflowFeatureRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SubworkflowFeatureRequirement;
+
+/// Indicates whether a `CommandLineTool` needs outbound network access.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#NetworkAccess
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkAccess {
+    pub network_access: NetworkAccessValue,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum NetworkAccessValue {
+    Enabled(bool),
+    Expression(String),
+}
+
+/// Specifies the default `loadListing` of `Directory` inputs.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#LoadListingRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct LoadListingRequirement {
+    pub load_listing: Option<LoadListing>,
+}
+
+/// Lists software packages that must be installed for a `CommandLineTool` to run.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#SoftwareRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SoftwareRequirement {
+    /// Packages in the declaration order, the map form `{name: {version: [..]}}` is
+    /// accepted and serialized as a list.
+    #[serde(deserialize_with = "deserialize_packages")]
+    pub packages: Vec<SoftwarePackage>,
+}
+
+/// Software package, e.g. `samtools` of versions `1.19` or `1.20`.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#SoftwarePackage
+#[skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SoftwarePackage {
+    pub package: String,
+    pub version: Option<Vec<String>>,
+    /// IRIs identifying the package in package repositories, e.g. bio.tools or conda.
+    pub specs: Option<Vec<String>>,
+}
+
+fn deserialize_packages<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<SoftwarePackage>, D::Error> {
+    let packages = match Value::deserialize(deserializer)? {
+        Value::Mapping(packages) => packages,
+        packages => return serde_yaml::from_value(packages).map_err(D::Error::custom),
+    };
+    packages
+        .into_iter()
+        .map(|(package, value)| {
+            let package: String = serde_yaml::from_value(package).map_err(D::Error::custom)?;
+            match value {
+                Value::Mapping(mut fields) => {
+                    fields.insert("package".into(), package.into());
+                    serde_yaml::from_value(Value::Mapping(fields)).map_err(D::Error::custom)
+                }
+                // `specs` is the map predicate of `SoftwarePackage`
+                Value::Null => Ok(SoftwarePackage {
+                    package,
+                    version: None,
+                    specs: None,
+                }),
+                Value::String(spec) => Ok(SoftwarePackage {
+                    package,
+                    version: None,
+                    specs: Some(vec![spec]),
+                }),
+                specs => Ok(SoftwarePackage {
+                    package,
+                    version: None,
+                    specs: serde_yaml::from_value(specs).map_err(D::Error::custom)?,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Declares named types that parameters can reference in their `type` field.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#SchemaDefRequirement
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SchemaDefRequirement {
+    pub types: Vec<CwlTypeDef>,
+}
+
 /// Specifies a reusing output from past work of a `CommandLineTool`.
 /// See: https://www.commonwl.org/v1.2/CommandLineTool.html#WorkReuse
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkReuse {
     pub enable_reuse: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use crate::schema::types::LoadListing;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "", LoadListing::NoListing)]
+    #[case("", "deep_listing", LoadListing::DeepListing)]
+    #[case("shallow_listing", "", LoadListing::ShallowListing)]
+    #[case("shallow_listing", "deep_listing", LoadListing::ShallowListing)]
+    #[case("no_listing", "deep_listing", LoadListing::NoListing)]
+    fn test_effective_load_listing(
+        #[case] parameter: &str,
+        #[case] requirement: &str,
+        #[case] expected: LoadListing,
+        #[values("requirements", "hints")] section: &str,
+    ) {
+        let parameter = match parameter {
+            "" => String::new(),
+            mode => format!("    loadListing: {mode}\n"),
+        };
+        let requirement = match requirement {
+            "" => String::new(),
+            mode => format!("  - class: LoadListingRequirement\n    loadListing: {mode}\n"),
+        };
+        let inputs = format!("inputs:\n  - id: dir\n    type: Directory\n{parameter}");
+        let requirements = match section {
+            "hints" => format!("requirements: []\nhints:\n{requirement}"),
+            _ => format!("requirements:\n{requirement}"),
+        };
+
+        let clt = format!(
+            "cwlVersion: v1.2\nclass: CommandLineTool\n{inputs}outputs: []\n{requirements}"
+        );
+        let CwlSchema::CommandLineTool(tool) = clt.parse().unwrap() else {
+            panic!("Expected CommandLineTool");
+        };
+        assert_eq!(tool.effective_load_listing("dir"), expected);
+
+        let wf = format!(
+            "cwlVersion: v1.2\nclass: Workflow\n{inputs}outputs: []\nsteps: []\n{requirements}"
+        );
+        let CwlSchema::Workflow(workflow) = wf.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(workflow.effective_load_listing("dir"), expected);
+    }
+
+    #[test]
+    fn test_map_form_requirements() {
+        let map_form = "cwlVersion: v1.2\nclass: Workflow\n\
+                        requirements:\n  InlineJavascriptRequirement: {}\n  SubworkflowFeatureRequirement:\n\
+                        inputs: []\noutputs: []\n\
+                        steps:\n- id: echo\n  in: []\n  out: []\n  run:\n    class: CommandLineTool\n\
+                        \x20   requirements:\n      DockerRequirement:\n        dockerPull: debian:12.8\n\
+                        \x20     ResourceRequirement:\n        coresMin: 4\n\
+                        \x20   hints:\n    - class: WorkReuse\n      enableReuse: false\n";
+        let list_form = "cwlVersion: v1.2\nclass: Workflow\n\
+                         requirements:\n- class: InlineJavascriptRequirement\n- class: SubworkflowFeatureRequirement\n\
+                         inputs: []\noutputs: []\n\
+                         steps:\n- id: echo\n  in: []\n  out: []\n  run:\n    class: CommandLineTool\n\
+                         \x20   requirements:\n    - class: DockerRequirement\n      dockerPull: debian:12.8\n\
+                         \x20   - class: ResourceRequirement\n      coresMin: 4\n\
+                         \x20   hints:\n      WorkReuse: {enableReuse: false}\n";
+        let map_form: CwlSchema = map_form.parse().unwrap();
+        let list_form: CwlSchema = list_form.parse().unwrap();
+        assert_eq!(
+            map_form.to_string().unwrap(),
+            list_form.to_string().unwrap()
+        );
+
+        let CwlSchema::Workflow(workflow) = map_form else {
+            panic!("Expected Workflow");
+        };
+        let tool = workflow.step("echo").unwrap().run.as_tool().unwrap();
+        assert!(matches!(
+            &tool.requirements[..],
+            [
+                CommandLineToolRequirement::DockerRequirement(_),
+                CommandLineToolRequirement::ResourceRequirement(_)
+            ]
+        ));
+        assert!(matches!(
+            &tool.hints[..],
+            [Hint::Requirement(CommandLineToolRequirement::WorkReuse(reuse))] if !reuse.enable_reuse
+        ));
+    }
+}
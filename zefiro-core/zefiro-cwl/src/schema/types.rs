@@ -1,10 +1,31 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, fmt};
 
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::skip_serializing_none;
 use serde_yaml::Value as YValue;
 
+use crate::error::{Severity, ValidationError};
+use crate::schema::command_line_tool::InputBinding;
+
 pub const WF_CWL_CLASS: &str = "Workflow";
 pub const CLT_CWL_CLASS: &str = "CommandLineTool";
+pub const OPERATION_CWL_CLASS: &str = "Operation";
+
+/// Fields of an object that are not part of the CWL object model, e.g. extension fields
+/// like `zefiro:queue` or `s:author`, they are written back as is.
+pub type Extensions = BTreeMap<String, YValue>;
+
+/// Prefixes declared in `$namespaces` mapped to IRIs, e.g. `edam` to `http://edamontology.org/`.
+pub type Namespaces = BTreeMap<String, String>;
+
+/// Expands the prefix of a `prefix:name` identifier declared in `namespaces`, e.g.
+/// `edam:format_1930` to `http://edamontology.org/format_1930`, other identifiers are
+/// returned as is.
+pub fn expand_prefix(id: &str, namespaces: &Namespaces) -> String {
+    id.split_once(':')
+        .and_then(|(prefix, name)| Some(format!("{}{name}", namespaces.get(prefix)?)))
+        .unwrap_or_else(|| id.to_string())
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
@@ -12,7 +33,7 @@ pub enum Any {
     Any(YValue),
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(untagged)]
 pub enum CwlSchemaType {
     /// Represents any value in field `type`
@@ -32,16 +53,319 @@ pub enum CwlSchemaType {
     ///   items: File
     Array(Vec<Self>),
 
+    /// Represents a record type
+    ///
+    /// Example:
+    ///
+    /// type: record
+    /// fields:
+    ///   - name: sample
+    ///     type: string
+    Record(RecordType),
+
+    /// Represents an enum type
+    ///
+    /// Example:
+    ///
+    /// type: enum
+    /// symbols: [sorted, unsorted]
+    Enum(EnumType),
+
     /// Represents a map type
     ///
     /// Example:
     ///
     /// type: array
     /// items: string
-    Map(HashMap<String, Self>),
+    Map(BTreeMap<String, Self>),
+}
+
+impl CwlSchemaType {
+    /// Expands the type name shorthands `File?`, `File[]` and `File[]?` into
+    /// `[null, File]` and `{type: array, items: File}`, other names are kept as is.
+    pub fn from_shorthand(name: &str) -> Self {
+        if let Some(r#type) = name.strip_suffix('?') {
+            return Self::Array(vec![
+                Self::Any("null".to_string()),
+                Self::from_shorthand(r#type),
+            ]);
+        }
+        if let Some(items) = name.strip_suffix("[]") {
+            return Self::Map(BTreeMap::from([
+                ("type".to_string(), Self::Any("array".to_string())),
+                ("items".to_string(), Self::from_shorthand(items)),
+            ]));
+        }
+        Self::Any(name.to_string())
+    }
+
+    /// Returns `true` if the type accepts `null`, e.g. `File?` or `[null, File]`.
+    pub fn is_optional(&self) -> bool {
+        match self {
+            Self::Any(name) => name == "null",
+            Self::Array(types) => types.iter().any(Self::is_null),
+            Self::Record(_) | Self::Enum(_) | Self::Map(_) => false,
+        }
+    }
+
+    /// Returns type of the array items, e.g. `File` for `File[]` or `File[]?`,
+    /// `None` if the type is not an array.
+    pub fn item_type(&self) -> Option<&Self> {
+        match self {
+            Self::Map(map) => match (map.get("type"), map.get("items")) {
+                (Some(Self::Any(kind)), Some(items)) if kind == "array" => Some(items),
+                _ => None,
+            },
+            Self::Array(types) => {
+                match types.iter().filter(|t| !t.is_null()).collect::<Vec<_>>()[..] {
+                    [single] => single.item_type(),
+                    _ => None,
+                }
+            }
+            Self::Any(_) | Self::Record(_) | Self::Enum(_) => None,
+        }
+    }
+
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, Self::Any(name) if name == "null")
+    }
+}
+
+impl<'de> Deserialize<'de> for CwlSchemaType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = YValue::deserialize(deserializer)?;
+        let r#type = match value {
+            YValue::String(name) => return Ok(Self::from_shorthand(&name)),
+            YValue::Sequence(_) => serde_yaml::from_value(value).map(Self::Array),
+            YValue::Mapping(ref mapping) => match mapping.get("type").and_then(YValue::as_str) {
+                Some("record") => serde_yaml::from_value(value).map(Self::Record),
+                Some("enum") => serde_yaml::from_value(value).map(Self::Enum),
+                _ => serde_yaml::from_value(value).map(Self::Map),
+            },
+            _ => {
+                return Err(D::Error::custom(
+                    "expected a type name, a list of types or a type definition",
+                ))
+            }
+        };
+        r#type.map_err(D::Error::custom)
+    }
+}
+
+/// Represents an inline `record` type with named fields.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandInputRecordSchema
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename = "record")]
+pub struct RecordType {
+    pub name: Option<String>,
+    pub label: Option<String>,
+    pub doc: Option<Documentation>,
+    pub fields: RecordFields,
+}
+
+/// Represents an inline `enum` type, a string restricted to the listed symbols.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandInputEnumSchema
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(tag = "type", rename = "enum")]
+pub struct EnumType {
+    pub name: Option<String>,
+    pub label: Option<String>,
+    pub doc: Option<Documentation>,
+    pub symbols: Vec<String>,
+}
+
+/// Returns `true` if `value` is one of `symbols`, symbols may be namespaced by the type name,
+/// e.g. `#mode/sorted`.
+pub(crate) fn is_enum_symbol(symbols: &[String], value: &str) -> bool {
+    symbols
+        .iter()
+        .any(|symbol| symbol == value || fragment(symbol).rsplit('/').next() == Some(value))
+}
+
+/// Fields of a `RecordType` keeping the shape they are declared with: a list of fields
+/// or a map from field names to types or fields.
+#[derive(Clone, Debug)]
+pub enum RecordFields {
+    List(Vec<RecordField>),
+    Map(Vec<RecordField>),
+}
+
+impl RecordFields {
+    /// Returns fields in the declaration order.
+    pub fn fields(&self) -> &[RecordField] {
+        match self {
+            Self::List(fields) | Self::Map(fields) => fields,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = match YValue::deserialize(deserializer)? {
+            YValue::Mapping(fields) => fields,
+            fields => {
+                return serde_yaml::from_value(fields)
+                    .map(Self::List)
+                    .map_err(D::Error::custom)
+            }
+        };
+        fields
+            .into_iter()
+            .map(|(name, field)| match field {
+                YValue::Mapping(mut field) => {
+                    field.insert("name".into(), name);
+                    serde_yaml::from_value(YValue::Mapping(field))
+                }
+                // `type` is the map predicate of record fields
+                r#type => Ok(RecordField {
+                    name: serde_yaml::from_value(name)?,
+                    r#type: serde_yaml::from_value(r#type)?,
+                    label: None,
+                    doc: None,
+                    input_binding: None,
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::Map)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for RecordFields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// Field of the map shape, the name is the key.
+        #[skip_serializing_none]
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct MapField<'a> {
+            r#type: &'a CwlSchemaType,
+            label: Option<&'a String>,
+            doc: Option<&'a Documentation>,
+            input_binding: Option<&'a InputBinding>,
+        }
+
+        let fields = match self {
+            Self::List(fields) => return fields.serialize(serializer),
+            Self::Map(fields) => fields,
+        };
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for field in fields {
+            if field.label.is_none() && field.doc.is_none() && field.input_binding.is_none() {
+                map.serialize_entry(&field.name, &field.r#type)?;
+            } else {
+                map.serialize_entry(
+                    &field.name,
+                    &MapField {
+                        r#type: &field.r#type,
+                        label: field.label.as_ref(),
+                        doc: field.doc.as_ref(),
+                        input_binding: field.input_binding.as_ref(),
+                    },
+                )?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Any {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // `null` is skipped: `default: null` is deserialized as a missing value
+        Ok(Self::Any(match u.int_in_range(0..=2)? {
+            0 => YValue::Bool(u.arbitrary()?),
+            1 => YValue::Number(u.arbitrary::<i64>()?.into()),
+            _ => YValue::String(u.arbitrary()?),
+        }))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CwlSchemaType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::arbitrary_with_depth(u, crate::arbitrary_helpers::MAX_DEPTH)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl CwlSchemaType {
+    fn arbitrary_with_depth(
+        u: &mut arbitrary::Unstructured<'_>,
+        depth: usize,
+    ) -> arbitrary::Result<Self> {
+        use crate::arbitrary_helpers::arbitrary_vec;
+
+        // Names are expanded as if deserialized, so that shorthands roundtrip
+        if depth == 0 {
+            return Ok(Self::from_shorthand(u.arbitrary()?));
+        }
+        Ok(match u.int_in_range(0..=4)? {
+            0 => Self::from_shorthand(u.arbitrary()?),
+            1 => Self::Array(arbitrary_vec(u, |u| {
+                Self::arbitrary_with_depth(u, depth - 1)
+            })?),
+            2 => Self::Record(RecordType {
+                name: u.arbitrary()?,
+                label: u.arbitrary()?,
+                doc: None,
+                fields: RecordFields::List(arbitrary_vec(u, |u| {
+                    Ok(RecordField {
+                        name: u.arbitrary()?,
+                        r#type: Self::arbitrary_with_depth(u, depth - 1)?,
+                        label: u.arbitrary()?,
+                        doc: None,
+                        input_binding: u.arbitrary()?,
+                    })
+                })?),
+            }),
+            3 => Self::Enum(u.arbitrary()?),
+            _ => Self::Map(
+                arbitrary_vec(u, |u| {
+                    Ok((u.arbitrary()?, Self::arbitrary_with_depth(u, depth - 1)?))
+                })?
+                .into_iter()
+                .collect(),
+            ),
+        })
+    }
+}
+
+impl fmt::Display for CwlSchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any(name) => write!(f, "{name}"),
+            Self::Array(types) => {
+                let non_null: Vec<&Self> = types.iter().filter(|t| !t.is_null()).collect();
+                match non_null.as_slice() {
+                    [single] if non_null.len() < types.len() => write!(f, "{single}?"),
+                    _ => {
+                        let names: Vec<String> = types.iter().map(Self::to_string).collect();
+                        write!(f, "{}", names.join(" | "))
+                    }
+                }
+            }
+            Self::Record(record) => match &record.name {
+                Some(name) => write!(f, "{}", fragment(name)),
+                None => write!(f, "record"),
+            },
+            Self::Enum(r#enum) => match &r#enum.name {
+                Some(name) => write!(f, "{}", fragment(name)),
+                None => write!(f, "enum"),
+            },
+            Self::Map(map) => match (map.get("type"), map.get("items")) {
+                (Some(Self::Any(name)), Some(items)) if name == "array" => write!(f, "{items}[]"),
+                (Some(r#type), _) => write!(f, "{}", r#type),
+                _ => write!(f, "record"),
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum Documentation {
     SingleLine(String),
@@ -49,13 +373,34 @@ pub enum Documentation {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum Format {
     Format(String),
     Formats(Vec<String>),
 }
 
+impl Format {
+    /// Returns accepted format identifiers, e.g. `edam:format_1930`.
+    pub fn formats(&self) -> Vec<&str> {
+        match self {
+            Self::Format(format) => vec![format],
+            Self::Formats(formats) => formats.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// Returns `true` if `format` is accepted or accepted formats are given by an expression,
+    /// prefixes of both are expanded with `namespaces` before comparing.
+    pub fn accepts(&self, format: &str, namespaces: &Namespaces) -> bool {
+        let format = expand_prefix(format, namespaces);
+        self.formats().into_iter().any(|accepted| {
+            accepted.starts_with('$') || expand_prefix(accepted, namespaces) == format
+        })
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum Scatter {
     Parameter(String),
@@ -63,8 +408,274 @@ pub enum Scatter {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum Source {
     SingleSource(String),
     MultiSources(Vec<String>),
 }
+
+/// Method to merge values of multiple sources of a step input or a workflow output.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#LinkMergeMethod
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMerge {
+    /// Each source becomes an item of the list, this is the default method.
+    MergeNested,
+    /// Sources that are lists are concatenated, single values are appended.
+    MergeFlattened,
+}
+
+/// Method to pick non-null values of multiple sources, e.g. outputs of conditional steps.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#PickValueMethod
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum PickValue {
+    /// The first non-null value, fails if all values are null.
+    FirstNonNull,
+    /// The only non-null value, fails if there are none or more than one.
+    TheOnlyNonNull,
+    /// List of all non-null values, may be empty.
+    AllNonNull,
+}
+
+/// Returns `true` if the expression needs a JavaScript engine, i.e. it's not just text
+/// with parameter references like `$(inputs.reads.basename)`.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#Parameter_references
+pub(crate) fn is_javascript_expression(expression: &str) -> bool {
+    if expression.contains("${") {
+        return true;
+    }
+    let mut rest = expression;
+    while let Some(start) = rest.find("$(") {
+        let Some(end) = rest[start..].find(')').map(|end| end + start) else {
+            return true;
+        };
+        if !is_parameter_reference(&rest[start + 2..end]) {
+            return true;
+        }
+        rest = &rest[end + 1..];
+    }
+    false
+}
+
+/// Returns an error if the `expression` at `path` needs JavaScript, but
+/// `InlineJavascriptRequirement` is not declared.
+pub(crate) fn javascript_error(
+    path: String,
+    expression: &str,
+    javascript: bool,
+) -> Option<ValidationError> {
+    (!javascript && is_javascript_expression(expression)).then(|| ValidationError {
+        severity: Severity::Error,
+        path,
+        message: "JavaScript expression requires InlineJavascriptRequirement".to_string(),
+    })
+}
+
+/// Checks the `inputs.reads.basename`, `self[0]` or `inputs['reads']` reference syntax.
+fn is_parameter_reference(reference: &str) -> bool {
+    let is_symbol =
+        |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let end = reference.find(['.', '[']).unwrap_or(reference.len());
+    let (root, mut segments) = reference.split_at(end);
+    if !matches!(root, "inputs" | "self" | "runtime") {
+        return false;
+    }
+    while !segments.is_empty() {
+        if let Some(rest) = segments.strip_prefix('.') {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if !is_symbol(&rest[..end]) {
+                return false;
+            }
+            segments = &rest[end..];
+        } else if let Some((index, rest)) = segments
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+        {
+            let quoted = ["'", "\""].iter().any(|quote| {
+                index.len() >= 2 && index.starts_with(quote) && index.ends_with(quote)
+            });
+            let numeric = !index.is_empty() && index.chars().all(|c| c.is_ascii_digit());
+            if !quoted && !numeric {
+                return false;
+            }
+            segments = rest;
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+/// Controls how much of a `Directory` listing is loaded before running the process.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#LoadListingEnum
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum LoadListing {
+    #[default]
+    NoListing,
+    ShallowListing,
+    DeepListing,
+}
+
+/// Named type declared in `SchemaDefRequirement` and referenced by name, e.g. `type: "#sample"`.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#SchemaDefRequirement
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct CwlTypeDef {
+    /// Definition of the type: a `record`, an `enum` or an `array`.
+    pub r#type: CwlSchemaType,
+}
+
+impl CwlTypeDef {
+    /// Returns `name` of the type definition.
+    pub fn name(&self) -> Option<&str> {
+        match &self.r#type {
+            CwlSchemaType::Record(record) => record.name.as_deref(),
+            CwlSchemaType::Enum(r#enum) => r#enum.name.as_deref(),
+            CwlSchemaType::Map(map) => match map.get("name") {
+                Some(CwlSchemaType::Any(name)) => Some(name),
+                _ => None,
+            },
+            CwlSchemaType::Any(_) | CwlSchemaType::Array(_) => None,
+        }
+    }
+
+    /// Returns `true` if the type is referenced by `name`, e.g. `#sample` or `types.yml#sample`.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name()
+            .is_some_and(|own| fragment(own) == fragment(name))
+    }
+}
+
+impl<'de> Deserialize<'de> for CwlTypeDef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def = Self {
+            r#type: CwlSchemaType::deserialize(deserializer)?,
+        };
+        match def.name() {
+            Some(_) => Ok(def),
+            None => Err(D::Error::custom(
+                "expected a named record, enum or array type definition",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CwlTypeDef {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let name = Some(u.arbitrary()?);
+        let r#type = match u.arbitrary()? {
+            CwlSchemaType::Record(record) => CwlSchemaType::Record(RecordType { name, ..record }),
+            _ => CwlSchemaType::Enum(EnumType {
+                name,
+                ..u.arbitrary()?
+            }),
+        };
+        Ok(Self { r#type })
+    }
+}
+
+/// Strips the document part of a type reference, e.g. `types.yml#sample` -> `sample`.
+fn fragment(name: &str) -> &str {
+    name.rsplit_once('#').map_or(name, |(_, fragment)| fragment)
+}
+
+/// Represents a field of a record type.
+/// See: https://www.commonwl.org/v1.2/CommandLineTool.html#CommandInputRecordField
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub struct RecordField {
+    pub name: String,
+    pub r#type: CwlSchemaType,
+    pub label: Option<String>,
+    pub doc: Option<Documentation>,
+    pub input_binding: Option<InputBinding>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("File", "File", false)]
+    #[case("File?", "File?", true)]
+    #[case("[\"null\", File]", "File?", true)]
+    #[case("[string, int]", "string | int", false)]
+    #[case("{type: array, items: File}", "File[]", false)]
+    #[case("{type: record, fields: [{name: a, type: int}]}", "record", false)]
+    #[case("{type: record, name: '#sample', fields: {a: int}}", "sample", false)]
+    #[case("{type: enum, symbols: [sorted, unsorted]}", "enum", false)]
+    #[case("[\"null\", {type: enum, name: '#mode', symbols: [a]}]", "mode?", true)]
+    fn test_cwlschematype_display(
+        #[case] yaml: &str,
+        #[case] expected: &str,
+        #[case] optional: bool,
+    ) {
+        let schema_type: CwlSchemaType = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schema_type.to_string(), expected);
+        assert_eq!(schema_type.is_optional(), optional);
+    }
+
+    #[rstest]
+    #[case("File", false, None)]
+    #[case("File?", true, None)]
+    #[case("File[]", false, Some("File"))]
+    #[case("File[]?", true, Some("File"))]
+    #[case("string[][]", false, Some("string[]"))]
+    #[case("[\"null\", {type: array, items: File}]", true, Some("File"))]
+    fn test_cwlschematype_shorthand(
+        #[case] yaml: &str,
+        #[case] optional: bool,
+        #[case] item_type: Option<&str>,
+    ) {
+        let schema_type: CwlSchemaType = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schema_type.is_optional(), optional);
+        assert_eq!(
+            schema_type.item_type().map(ToString::to_string).as_deref(),
+            item_type
+        );
+    }
+
+    #[rstest]
+    #[case("File?", "- 'null'\n- File\n")]
+    #[case("File[]", "items: File\ntype: array\n")]
+    #[case("File[]?", "- 'null'\n- items: File\n  type: array\n")]
+    fn test_cwlschematype_shorthand_serialize(#[case] yaml: &str, #[case] expected: &str) {
+        let schema_type: CwlSchemaType = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(serde_yaml::to_string(&schema_type).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "type: record\nfields:\n- name: sample\n  type: string\n  label: Sample name\n- name: reads\n  type: File\n  inputBinding:\n    prefix: --reads\n"
+    )]
+    #[case(
+        "type: record\nfields:\n  sample: string\n  reads:\n    type: File\n    inputBinding:\n      prefix: --reads\n"
+    )]
+    fn test_record_type_shape(#[case] yaml: &str) {
+        let CwlSchemaType::Record(record) = serde_yaml::from_str(yaml).unwrap() else {
+            panic!("Expected Record");
+        };
+        let fields = record.fields.fields();
+        assert_eq!(fields[0].name, "sample");
+        assert_eq!(fields[0].r#type.to_string(), "string");
+        assert_eq!(fields[1].name, "reads");
+        assert_eq!(
+            fields[1].input_binding.as_ref().unwrap().prefix.as_deref(),
+            Some("--reads")
+        );
+        assert_eq!(
+            serde_yaml::to_string(&CwlSchemaType::Record(record)).unwrap(),
+            yaml
+        );
+    }
+}
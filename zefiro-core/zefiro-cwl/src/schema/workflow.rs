@@ -1,13 +1,30 @@
+use crate::error::{CwlError, Severity, ValidationError};
 use crate::schema::command_line_tool::CommandLineTool;
-use crate::schema::requirements::{WorkflowRequirement, MINIMAL_CWL_VERSION};
-use crate::schema::types::{Any, CwlSchemaType, Documentation, Scatter, Source, WF_CWL_CLASS};
-use serde::{Deserialize, Serialize};
+use crate::schema::document::CwlSchema;
+use crate::schema::index::{
+    deserialize_parameters, deserialize_step_inputs, deserialize_steps, id_errors, IdIndex,
+};
+use crate::schema::operation::Operation;
+use crate::schema::preprocess::DocumentStack;
+use crate::schema::requirements::{
+    default_load_listing, deserialize_requirements, Hint, InlineJavascriptRequirement,
+    LoadListingRequirement, Requirement, WorkflowRequirement, CWL_VERSION_1_0, MINIMAL_CWL_VERSION,
+};
+use crate::schema::types::{
+    javascript_error, Any, CwlSchemaType, CwlTypeDef, Documentation, Extensions, Format, LinkMerge,
+    LoadListing, PickValue, Scatter, Source, WF_CWL_CLASS,
+};
+use crate::schema::types::{CLT_CWL_CLASS, OPERATION_CWL_CLASS};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
+use serde_yaml::Value;
+use std::path::Path;
 
 /// This defines the schema of the CWL Workflow Description document.
 /// See: https://www.commonwl.org/v1.2/Workflow.html
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct Workflow {
     #[serde(default = "Workflow::default_cwl_version")]
@@ -18,10 +35,36 @@ pub struct Workflow {
     #[serde(default)]
     pub id: String,
     pub label: Option<String>,
+    #[serde(deserialize_with = "deserialize_parameters")]
     pub inputs: Vec<WorkflowInputParameter>,
+    #[serde(deserialize_with = "deserialize_parameters")]
     pub outputs: Vec<WorkflowOutputParameter>,
+    #[serde(deserialize_with = "deserialize_steps")]
     pub steps: Vec<WorkflowStep>,
+    #[serde(deserialize_with = "deserialize_requirements")]
     pub requirements: Vec<WorkflowRequirement>,
+    /// Requirements that the runner may ignore, they apply to the steps as well.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_requirements"
+    )]
+    pub hints: Vec<Hint<WorkflowRequirement>>,
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    input_index: IdIndex,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    output_index: IdIndex,
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    step_index: IdIndex,
 }
 
 impl Workflow {
@@ -32,24 +75,350 @@ impl Workflow {
     fn default_class() -> String {
         WF_CWL_CLASS.to_string()
     }
+
+    /// Returns input parameter with the given `id`.
+    pub fn input(&self, id: &str) -> Option<&WorkflowInputParameter> {
+        self.input_index
+            .get(&self.inputs, id, |input| input.id.as_deref())
+    }
+
+    /// Returns output parameter with the given `id`.
+    pub fn output(&self, id: &str) -> Option<&WorkflowOutputParameter> {
+        self.output_index
+            .get(&self.outputs, id, |output| output.id.as_deref())
+    }
+
+    /// Returns step with the given `id`.
+    pub fn step(&self, id: &str) -> Option<&WorkflowStep> {
+        self.step_index
+            .get(&self.steps, id, |step| step.id.as_deref())
+    }
+
+    /// Returns requirement of the type `T` declared in requirements or, if there is none,
+    /// in hints of the workflow.
+    pub fn effective_requirement<T: Requirement>(&self) -> Option<&T> {
+        self.requirements
+            .iter()
+            .chain(self.hints.iter().filter_map(Hint::requirement))
+            .find_map(T::from_workflow_requirement)
+    }
+
+    /// Returns `loadListing` of the input, falling back to `LoadListingRequirement` of
+    /// requirements or hints and then to the default of the CWL version.
+    pub fn effective_load_listing(&self, input_id: &str) -> LoadListing {
+        self.input(input_id)
+            .and_then(|input| input.load_listing)
+            .or_else(|| {
+                self.effective_requirement::<LoadListingRequirement>()
+                    .and_then(|requirement| requirement.load_listing)
+            })
+            .unwrap_or_else(|| default_load_listing(&self.cwl_version))
+    }
+
+    /// Upgrades the workflow and inline processes of its steps to `MINIMAL_CWL_VERSION`,
+    /// see `CwlSchema::upgrade`.
+    pub fn upgrade(&mut self) {
+        for hint in &mut self.hints {
+            hint.upgrade();
+        }
+        if self.cwl_version == CWL_VERSION_1_0
+            && self
+                .effective_requirement::<LoadListingRequirement>()
+                .is_none()
+        {
+            self.requirements
+                .push(WorkflowRequirement::LoadListingRequirement(
+                    LoadListingRequirement {
+                        load_listing: Some(LoadListing::DeepListing),
+                    },
+                ));
+        }
+        for step in &mut self.steps {
+            match &mut step.run {
+                StepRun::CommandLineTool(tool) => tool.upgrade(),
+                StepRun::Workflow(workflow) => workflow.upgrade(),
+                StepRun::Operation(operation) => operation.upgrade(),
+                StepRun::Path(_) => {}
+            }
+        }
+        self.cwl_version = MINIMAL_CWL_VERSION.to_string();
+    }
+
+    /// Iterates over types declared in `SchemaDefRequirement`s of the workflow.
+    pub fn type_defs(&self) -> impl Iterator<Item = &CwlTypeDef> {
+        self.requirements
+            .iter()
+            .filter_map(|requirement| match requirement {
+                WorkflowRequirement::SchemaDefRequirement(schema_def) => Some(&schema_def.types),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    /// Iterates over `(id, input)` pairs in the declaration order.
+    ///
+    /// Missing ids are yielded as empty strings, see `Workflow::validate_ids`.
+    pub fn inputs(&self) -> impl Iterator<Item = (&str, &WorkflowInputParameter)> {
+        self.inputs
+            .iter()
+            .map(|input| (input.id.as_deref().unwrap_or_default(), input))
+    }
+
+    /// Iterates over `(id, output)` pairs in the declaration order.
+    pub fn outputs(&self) -> impl Iterator<Item = (&str, &WorkflowOutputParameter)> {
+        self.outputs
+            .iter()
+            .map(|output| (output.id.as_deref().unwrap_or_default(), output))
+    }
+
+    /// Iterates over `(id, step)` pairs in the declaration order.
+    pub fn steps(&self) -> impl Iterator<Item = (&str, &WorkflowStep)> {
+        self.steps
+            .iter()
+            .map(|step| (step.id.as_deref().unwrap_or_default(), step))
+    }
+
+    pub(crate) fn normalize_stream_outputs(&mut self) {
+        for step in &mut self.steps {
+            step.run.normalize_stream_outputs();
+        }
+    }
+
+    /// Replaces `run` paths of the steps, including steps of nested workflows, with the
+    /// processes loaded from the files, relative paths are resolved against `base_dir`.
+    pub fn resolve_runs(&mut self, base_dir: &Path) -> Result<(), CwlError> {
+        self.resolve_runs_in(base_dir, &mut vec![])
+    }
+
+    pub(crate) fn resolve_runs_in(
+        &mut self,
+        base_dir: &Path,
+        stack: &mut DocumentStack,
+    ) -> Result<(), CwlError> {
+        for step in &mut self.steps {
+            match &mut step.run {
+                StepRun::Path(path) => {
+                    let path = base_dir.join(path.as_str());
+                    let process =
+                        CwlSchema::load(&path, stack).map_err(|source| CwlError::StepRun {
+                            step: step.id.clone().unwrap_or_default(),
+                            path: path.display().to_string(),
+                            source: Box::new(source),
+                        })?;
+                    step.run = match process {
+                        CwlSchema::CommandLineTool(tool) => {
+                            StepRun::CommandLineTool(Box::new(tool))
+                        }
+                        CwlSchema::Workflow(workflow) => StepRun::Workflow(Box::new(workflow)),
+                        CwlSchema::Operation(operation) => StepRun::Operation(Box::new(operation)),
+                    };
+                }
+                StepRun::Workflow(workflow) => workflow.resolve_runs_in(base_dir, stack)?,
+                StepRun::CommandLineTool(_) | StepRun::Operation(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that all inputs, outputs and steps have unique ids, including tools of the steps.
+    pub fn validate_ids(&self) -> Vec<ValidationError> {
+        let mut errors = id_errors("inputs", self.inputs.iter().map(|i| i.id.as_deref()));
+        errors.extend(id_errors(
+            "outputs",
+            self.outputs.iter().map(|o| o.id.as_deref()),
+        ));
+        errors.extend(id_errors(
+            "steps",
+            self.steps.iter().map(|s| s.id.as_deref()),
+        ));
+        for (position, step) in self.steps.iter().enumerate() {
+            errors.extend(step.run.validate_ids().into_iter().map(|mut error| {
+                error.path = format!("steps[{position}].run.{}", error.path);
+                error
+            }));
+        }
+        errors
+    }
+
+    /// Checks that features used by the workflow and its nested workflows are enabled
+    /// by requirements, e.g. multiple sources need `MultipleInputFeatureRequirement`.
+    pub fn validate_requirements(&self) -> Vec<ValidationError> {
+        self.requirement_errors(false, false)
+    }
+
+    /// Requirements of a workflow are inherited by its nested workflows.
+    fn requirement_errors(
+        &self,
+        inherited_multiple_inputs: bool,
+        inherited_javascript: bool,
+    ) -> Vec<ValidationError> {
+        let multiple_inputs = inherited_multiple_inputs
+            || self.requirements.iter().any(|requirement| {
+                matches!(
+                    requirement,
+                    WorkflowRequirement::MultipleInputFeatureRequirement(_)
+                )
+            });
+        let javascript = inherited_javascript
+            || self
+                .effective_requirement::<InlineJavascriptRequirement>()
+                .is_some();
+        let missing = |path: String| ValidationError {
+            severity: Severity::Error,
+            path,
+            message: "Multiple sources require MultipleInputFeatureRequirement".to_string(),
+        };
+
+        let mut errors = vec![];
+        for (step_position, step) in self.steps.iter().enumerate() {
+            if let Some(when) = &step.when {
+                let path = format!("steps[{step_position}].when");
+                errors.extend(javascript_error(path, when, javascript));
+            }
+            for (input_position, input) in step.r#in.iter().enumerate() {
+                let path = format!("steps[{step_position}].in[{input_position}]");
+                if !multiple_inputs && matches!(input.source, Some(Source::MultiSources(_))) {
+                    errors.push(missing(format!("{path}.source")));
+                }
+                if let Some(value_from) = &input.value_from {
+                    errors.extend(javascript_error(
+                        format!("{path}.valueFrom"),
+                        value_from,
+                        javascript,
+                    ));
+                }
+            }
+            let run_errors = match &step.run {
+                StepRun::Workflow(workflow) => {
+                    workflow.requirement_errors(multiple_inputs, javascript)
+                }
+                StepRun::CommandLineTool(tool) => tool.requirement_errors(javascript),
+                StepRun::Operation(_) | StepRun::Path(_) => vec![],
+            };
+            errors.extend(run_errors.into_iter().map(|mut error| {
+                error.path = format!("steps[{step_position}].run.{}", error.path);
+                error
+            }));
+        }
+        for (position, output) in self.outputs.iter().enumerate() {
+            if !multiple_inputs
+                && matches!(
+                    output.output_source,
+                    Some(WorkflowOutputParameterOutputSource::OutputSourceArray(_))
+                )
+            {
+                errors.push(missing(format!("outputs[{position}].outputSource")));
+            }
+        }
+        errors
+    }
+
+    /// Checks that sources of step inputs and outputs, and scatter inputs refer to existing
+    /// parameters, step inputs not declared by the run process are reported as warnings.
+    pub fn validate_references(&self) -> Vec<ValidationError> {
+        let unknown = |path: String, source: &str| ValidationError {
+            severity: Severity::Error,
+            path,
+            message: format!("Unknown source '{source}'"),
+        };
+        let mut errors = vec![];
+        for (step_position, step) in self.steps.iter().enumerate() {
+            for (input_position, input) in step.r#in.iter().enumerate() {
+                let path = format!("steps[{step_position}].in[{input_position}]");
+                let sources = match &input.source {
+                    Some(Source::SingleSource(source)) => vec![source],
+                    Some(Source::MultiSources(sources)) => sources.iter().collect(),
+                    None => vec![],
+                };
+                for source in sources.into_iter().filter(|s| !self.resolves(s)) {
+                    errors.push(unknown(format!("{path}.source"), source));
+                }
+                if step.run.declares_input(&input.id) == Some(false) {
+                    errors.push(ValidationError {
+                        severity: Severity::Warning,
+                        path,
+                        message: format!("Input '{}' is not declared by the run process", input.id),
+                    });
+                }
+            }
+            let scatter = match &step.scatter {
+                Some(Scatter::Parameter(name)) => vec![name],
+                Some(Scatter::Parameters(names)) => names.iter().collect(),
+                None => vec![],
+            };
+            for name in scatter {
+                let id = name.trim_start_matches('#');
+                if !step.r#in.iter().any(|input| input.id == id) {
+                    errors.push(ValidationError {
+                        severity: Severity::Error,
+                        path: format!("steps[{step_position}].scatter"),
+                        message: format!("Unknown scatter input '{name}'"),
+                    });
+                }
+            }
+            if let StepRun::Workflow(workflow) = &step.run {
+                errors.extend(workflow.validate_references().into_iter().map(|mut error| {
+                    error.path = format!("steps[{step_position}].run.{}", error.path);
+                    error
+                }));
+            }
+        }
+        for (position, output) in self.outputs.iter().enumerate() {
+            let sources = match &output.output_source {
+                Some(WorkflowOutputParameterOutputSource::OutputSource(source)) => vec![source],
+                Some(WorkflowOutputParameterOutputSource::OutputSourceArray(sources)) => {
+                    sources.iter().collect()
+                }
+                None => vec![],
+            };
+            for source in sources.into_iter().filter(|s| !self.resolves(s)) {
+                errors.push(unknown(format!("outputs[{position}].outputSource"), source));
+            }
+        }
+        errors
+    }
+
+    /// Whether the `source` refers to an input of the workflow or to an output of its step,
+    /// the leading `#` of a fragment reference is ignored.
+    fn resolves(&self, source: &str) -> bool {
+        let source = source.trim_start_matches('#');
+        match source.split_once('/') {
+            Some((step_id, output_id)) => self
+                .step(step_id)
+                .is_some_and(|step| step.out.iter().any(|output| output.id == output_id)),
+            None => self.input(source).is_some(),
+        }
+    }
 }
 
 /// Represents an input parameter for a `Workflow`.
 /// See: https://www.commonwl.org/v1.2/Workflow.html#WorkflowInputParameter
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowInputParameter {
     pub r#type: CwlSchemaType,
     pub label: Option<String>,
+    pub doc: Option<Documentation>,
     pub default: Option<Any>,
     pub id: Option<String>,
+    pub format: Option<Format>,
+    pub streamable: Option<bool>,
+    pub load_listing: Option<LoadListing>,
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
 }
 
 /// Represents an output parameter for a `Workflow`.
 /// See: https://www.commonwl.org/v1.2/Workflow.html#WorkflowOutputParameter
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowOutputParameter {
     pub r#type: CwlSchemaType,
@@ -57,9 +426,20 @@ pub struct WorkflowOutputParameter {
     pub doc: Option<Documentation>,
     pub id: Option<String>,
     pub output_source: Option<WorkflowOutputParameterOutputSource>,
+    pub link_merge: Option<LinkMerge>,
+    pub pick_value: Option<PickValue>,
+    pub format: Option<Format>,
+    pub streamable: Option<bool>,
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum WorkflowOutputParameterOutputSource {
     OutputSource(String),
@@ -70,34 +450,459 @@ pub enum WorkflowOutputParameterOutputSource {
 /// See: https://www.commonwl.org/v1.2/Workflow.html#WorkflowStep
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowStep {
+    #[serde(deserialize_with = "deserialize_step_inputs")]
     pub r#in: Vec<WorkflowStepInput>,
     pub out: Vec<WorkflowStepOutput>,
-    pub run: CommandLineTool,
+    pub run: StepRun,
     pub id: Option<String>,
     pub label: Option<String>,
     pub doc: Option<Documentation>,
     pub scatter: Option<Scatter>,
     pub scatter_method: Option<String>,
+    /// Condition of the step execution, the step is skipped and its outputs are null
+    /// if the expression evaluates to `false`.
+    pub when: Option<String>,
+    #[serde(flatten)]
+    #[cfg_attr(
+        feature = "arbitrary",
+        arbitrary(with = crate::arbitrary_helpers::arbitrary_extensions)
+    )]
+    pub extensions: Extensions,
+}
+
+/// Process run by a `WorkflowStep`, chosen by its `class` field.
+/// See: https://www.commonwl.org/v1.2/Workflow.html#SubworkflowFeatureRequirement
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum StepRun {
+    CommandLineTool(Box<CommandLineTool>),
+    Workflow(Box<Workflow>),
+    Operation(Box<Operation>),
+    /// Path to the file with the process, see `Workflow::resolve_runs`.
+    Path(String),
+}
+
+impl StepRun {
+    /// Returns the tool run by the step, `None` for a nested workflow, an operation
+    /// or an unresolved path.
+    pub fn as_tool(&self) -> Option<&CommandLineTool> {
+        match self {
+            Self::CommandLineTool(tool) => Some(tool),
+            Self::Workflow(_) | Self::Operation(_) | Self::Path(_) => None,
+        }
+    }
+
+    /// Iterates over types declared in `SchemaDefRequirement`s of the process.
+    pub fn type_defs(&self) -> Box<dyn Iterator<Item = &CwlTypeDef> + '_> {
+        match self {
+            Self::CommandLineTool(tool) => Box::new(tool.type_defs()),
+            Self::Workflow(workflow) => Box::new(workflow.type_defs()),
+            Self::Operation(operation) => Box::new(operation.type_defs()),
+            Self::Path(_) => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn normalize_stream_outputs(&mut self) {
+        match self {
+            Self::CommandLineTool(tool) => tool.normalize_stream_outputs(),
+            Self::Workflow(workflow) => workflow.normalize_stream_outputs(),
+            Self::Operation(_) | Self::Path(_) => {}
+        }
+    }
+
+    /// Whether the process declares the input, `None` for an unresolved path.
+    fn declares_input(&self, id: &str) -> Option<bool> {
+        match self {
+            Self::CommandLineTool(tool) => Some(tool.input(id).is_some()),
+            Self::Workflow(workflow) => Some(workflow.input(id).is_some()),
+            Self::Operation(operation) => Some(operation.input(id).is_some()),
+            Self::Path(_) => None,
+        }
+    }
+
+    fn validate_ids(&self) -> Vec<ValidationError> {
+        match self {
+            Self::CommandLineTool(tool) => tool.validate_ids(),
+            Self::Workflow(workflow) => workflow.validate_ids(),
+            Self::Operation(operation) => operation.validate_ids(),
+            Self::Path(_) => vec![],
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StepRun {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        if let Value::String(path) = value {
+            return Ok(Self::Path(path));
+        }
+        let run = match value.get("class").and_then(Value::as_str) {
+            Some(CLT_CWL_CLASS) | None => {
+                serde_yaml::from_value(value).map(|tool| Self::CommandLineTool(Box::new(tool)))
+            }
+            Some(WF_CWL_CLASS) => {
+                serde_yaml::from_value(value).map(|workflow| Self::Workflow(Box::new(workflow)))
+            }
+            Some(OPERATION_CWL_CLASS) => {
+                serde_yaml::from_value(value).map(|operation| Self::Operation(Box::new(operation)))
+            }
+            Some(class) => {
+                return Err(D::Error::custom(format!(
+                    "Unsupported CWL document class: {class}"
+                )))
+            }
+        };
+        run.map_err(D::Error::custom)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for StepRun {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Nested workflows have no steps to keep the depth bounded
+        Ok(match u.int_in_range(0..=2)? {
+            0 => {
+                let mut tool: CommandLineTool = u.arbitrary()?;
+                tool.class = CLT_CWL_CLASS.to_string();
+                Self::CommandLineTool(Box::new(tool))
+            }
+            1 => Self::Workflow(Box::new(Workflow {
+                class: WF_CWL_CLASS.to_string(),
+                cwl_version: u.arbitrary()?,
+                doc: u.arbitrary()?,
+                id: u.arbitrary()?,
+                label: u.arbitrary()?,
+                inputs: u.arbitrary()?,
+                outputs: u.arbitrary()?,
+                requirements: u.arbitrary()?,
+                ..Default::default()
+            })),
+            _ => {
+                let mut operation: Operation = u.arbitrary()?;
+                operation.class = OPERATION_CWL_CLASS.to_string();
+                Self::Operation(Box::new(operation))
+            }
+        })
+    }
 }
 
 /// Defines the input parameters of the workflow step (`out` section).
 #[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowStepInput {
     pub id: String,
     pub source: Option<Source>,
+    pub link_merge: Option<LinkMerge>,
+    pub pick_value: Option<PickValue>,
     pub label: Option<String>,
     pub default: Option<Any>,
     pub value_from: Option<String>,
 }
 
-/// Defines the output parameters of the workflow step (`out` section).
+/// Defines the output parameters of the workflow step (`out` section), given as an object
+/// or just as an id.
 #[skip_serializing_none]
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "camelCase")]
 pub struct WorkflowStepOutput {
     pub id: String,
 }
+
+impl<'de> Deserialize<'de> for WorkflowStepOutput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Output {
+            id: String,
+        }
+
+        match Value::deserialize(deserializer)? {
+            Value::String(id) => Ok(Self { id }),
+            value => serde_yaml::from_value(value)
+                .map(|Output { id }| Self { id })
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use crate::schema::requirements::{DockerRequirement, ScatterFeatureRequirement};
+    use crate::schema::types::{LinkMerge, PickValue};
+    use rstest::rstest;
+
+    fn errors(errors: Vec<ValidationError>) -> Vec<String> {
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_workflow_lookup_by_id() {
+        let CwlSchema::Workflow(mut workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        assert!(workflow.validate_ids().is_empty());
+        assert!(workflow.input("step__out_file").is_some());
+        assert!(workflow.output("step__out_file").is_some());
+        let step = workflow.step("step").unwrap();
+        let tool = step.run.as_tool().unwrap();
+        assert_eq!(tool.input("in_file").unwrap().id, "in_file");
+        assert!(tool.output("missing").is_none());
+        assert_eq!(
+            workflow.inputs().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["step__in_file", "step__out_file"]
+        );
+
+        workflow.inputs.swap(0, 1);
+        assert_eq!(
+            workflow.input("step__in_file").unwrap().id.as_deref(),
+            Some("step__in_file")
+        );
+    }
+
+    #[test]
+    fn test_workflow_validate_ids() {
+        let CwlSchema::Workflow(mut workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-step-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        workflow.inputs[1].id = Some("step__in_file".to_string());
+        let StepRun::CommandLineTool(tool) = &mut workflow.steps[0].run else {
+            panic!("Expected CommandLineTool");
+        };
+        tool.inputs.push(tool.inputs[0].clone());
+        assert_eq!(
+            errors(workflow.validate_ids()),
+            [
+                "inputs[1].id: Duplicate id 'step__in_file'",
+                "steps[0].run.inputs[3].id: Duplicate id 'in_file'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_workflow_step() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-nested-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let StepRun::Workflow(inner) = &workflow.step("inner").unwrap().run else {
+            panic!("Expected nested Workflow");
+        };
+        let copy = inner.step("copy").unwrap().run.as_tool().unwrap();
+        assert_eq!(copy.base_command.as_ref().unwrap().parts(), vec!["cp"]);
+
+        let graph = crate::graph::dag::WorkflowGraph::from_workflow(&workflow);
+        let steps: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind == crate::graph::dag::NodeKind::Step)
+            .map(|node| node.id.as_str())
+            .collect();
+        assert_eq!(steps, vec!["inner"]);
+    }
+
+    #[test]
+    fn test_operation_step() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-operation-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let StepRun::Operation(summarize) = &workflow.step("summarize").unwrap().run else {
+            panic!("Expected Operation");
+        };
+        assert_eq!(
+            summarize.inputs().map(|(id, _)| id).collect::<Vec<_>>(),
+            vec!["bam"]
+        );
+        assert!(workflow.step("align").unwrap().run.as_tool().is_none());
+        assert!(workflow.validate_ids().is_empty());
+    }
+
+    #[test]
+    fn test_step_run_path() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/runs/wf-run-path-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let step = workflow.step("step").unwrap().run.as_tool().unwrap();
+        assert_eq!(
+            step.base_command.as_ref().unwrap().parts(),
+            vec!["python", "step.py"]
+        );
+        let copy = workflow.step("copy").unwrap().run.as_tool().unwrap();
+        assert_eq!(copy.base_command.as_ref().unwrap().parts(), vec!["cp"]);
+    }
+
+    #[test]
+    fn test_step_run_path_unresolved() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\nid: main\ninputs: []\noutputs: []\n\
+                    steps:\n- in: []\n  out: []\n  run: ../clt-step-schema.yml\n  id: step\n\
+                    requirements: []\n";
+        let schema: CwlSchema = yaml.parse().unwrap();
+        assert_eq!(schema.to_string().unwrap(), yaml);
+
+        let CwlSchema::Workflow(mut workflow) = schema else {
+            panic!("Expected Workflow");
+        };
+        assert!(
+            matches!(&workflow.steps[0].run, StepRun::Path(path) if path == "../clt-step-schema.yml")
+        );
+        workflow
+            .resolve_runs(Path::new("test_data/cwl/runs"))
+            .unwrap();
+        assert!(workflow.steps[0].run.as_tool().is_some());
+    }
+
+    #[test]
+    fn test_step_run_path_missing() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\ninputs: []\noutputs: []\nrequirements: []\n\
+                    steps:\n- id: step\n  in: []\n  out: []\n  run: missing.yml\n";
+        let CwlSchema::Workflow(mut workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        let error = workflow
+            .resolve_runs(Path::new("test_data/cwl/runs"))
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            CwlError::StepRun { step, path, .. }
+                if step == "step" && path == "test_data/cwl/runs/missing.yml"
+        ));
+    }
+
+    const MULTIPLE_SOURCES: &str = r#"
+cwlVersion: v1.2
+class: Workflow
+inputs:
+  - id: first
+    type: File
+  - id: second
+    type: File
+outputs:
+  - id: files
+    type:
+      type: array
+      items: File
+    outputSource: [first, second]
+    linkMerge: merge_flattened
+requirements: []
+steps:
+  - id: step
+    in:
+      - id: files
+        source: [first, second]
+        linkMerge: merge_nested
+    out: []
+    run:
+      class: CommandLineTool
+      inputs: []
+      outputs: []
+"#;
+
+    #[rstest]
+    #[case("merge_nested", LinkMerge::MergeNested)]
+    #[case("merge_flattened", LinkMerge::MergeFlattened)]
+    fn test_link_merge(#[case] name: &str, #[case] link_merge: LinkMerge) {
+        let yaml = MULTIPLE_SOURCES
+            .replace(
+                "requirements: []",
+                "requirements:\n  - class: MultipleInputFeatureRequirement",
+            )
+            .replace("linkMerge: merge_nested", &format!("linkMerge: {name}"));
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(workflow.steps[0].r#in[0].link_merge, Some(link_merge));
+        assert_eq!(
+            workflow.outputs[0].link_merge,
+            Some(LinkMerge::MergeFlattened)
+        );
+        assert!(workflow.validate_requirements().is_empty());
+    }
+
+    #[test]
+    fn test_multiple_sources_without_requirement() {
+        let CwlSchema::Workflow(workflow) = MULTIPLE_SOURCES.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        assert_eq!(
+            errors(workflow.validate_requirements()),
+            [
+                "steps[0].in[0].source: Multiple sources require MultipleInputFeatureRequirement",
+                "outputs[0].outputSource: Multiple sources require MultipleInputFeatureRequirement"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workflow_hints() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\nrequirements: []\n\
+                    hints:\n  DockerRequirement:\n    dockerPull: debian:12.8\n  ext:Queue:\n    name: gpu\n\
+                    \x20 ScatterFeatureRequirement: {}\n\
+                    inputs: []\noutputs: []\nsteps: []\n";
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        let docker = workflow
+            .effective_requirement::<DockerRequirement>()
+            .unwrap();
+        assert_eq!(docker.docker_pull.as_deref(), Some("debian:12.8"));
+        assert!(matches!(&workflow.hints[1], Hint::Unknown(hint) if hint["class"] == "ext:Queue"));
+        assert!(workflow
+            .effective_requirement::<ScatterFeatureRequirement>()
+            .is_some());
+    }
+
+    #[test]
+    fn test_conditional_steps() {
+        let CwlSchema::Workflow(workflow) =
+            CwlSchema::from_path("test_data/cwl/wf-conditional-schema.yml").unwrap()
+        else {
+            panic!("Expected Workflow");
+        };
+        let step = workflow.step("step1").unwrap();
+        assert_eq!(step.when.as_deref(), Some("$(inputs.a_new_var > 1)"));
+        assert_eq!(step.r#in[1].id, "a_new_var");
+        assert_eq!(step.out[0].id, "out1");
+        assert_eq!(
+            workflow.output("out1").unwrap().pick_value,
+            Some(PickValue::FirstNonNull)
+        );
+        assert!(workflow.validate_requirements().is_empty());
+    }
+
+    #[rstest]
+    #[case("$(inputs.test)", true)]
+    #[case("$(inputs['test'])", true)]
+    #[case("$(inputs.test == true)", false)]
+    #[case("${return inputs.test}", false)]
+    fn test_conditional_step_requirements(#[case] when: &str, #[case] valid: bool) {
+        let yaml = std::fs::read_to_string("test_data/cwl/wf-conditional-schema.yml")
+            .unwrap()
+            .replace("  InlineJavascriptRequirement: {}\n", "")
+            .replace("when: $(inputs.test)", &format!("when: \"{when}\""))
+            .replace("$(inputs.a_new_var > 1)", "$(inputs.in1)");
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        let expected: &[&str] = if valid {
+            &[]
+        } else {
+            &["steps[1].when: JavaScript expression requires InlineJavascriptRequirement"]
+        };
+        assert_eq!(errors(workflow.validate_requirements()), expected);
+    }
+}
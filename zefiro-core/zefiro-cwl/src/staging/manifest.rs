@@ -0,0 +1,345 @@
+use crate::error::CwlError;
+use crate::schema::types::{Any, Source};
+use crate::schema::workflow::{StepRun, Workflow};
+use crate::values::document::CwlValues;
+use crate::values::types::{CwlPath, CwlValueType};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Location schemes that the staging system can fetch, locations without a scheme are local paths.
+pub const SUPPORTED_SCHEMES: [&str; 5] = ["file", "s3", "gs", "http", "https"];
+
+/// Class of a staged location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum StagedClass {
+    File,
+    Directory,
+}
+
+/// Represents a remote file or directory that a workflow run reads.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedFile {
+    pub location: String,
+    pub class: StagedClass,
+    /// Ids of the steps that consume the location, sorted.
+    pub steps: Vec<String>,
+    pub size: Option<u64>,
+    pub checksum: Option<String>,
+    /// Scheme of the location is not one of `SUPPORTED_SCHEMES`.
+    pub unsupported_scheme: bool,
+}
+
+impl StagedFile {
+    fn new(location: &str, class: StagedClass) -> Self {
+        let unsupported_scheme = location
+            .split_once("://")
+            .is_some_and(|(scheme, _)| !SUPPORTED_SCHEMES.contains(&scheme));
+        Self {
+            location: location.to_string(),
+            class,
+            steps: vec![],
+            size: None,
+            checksum: None,
+            unsupported_scheme,
+        }
+    }
+}
+
+impl Workflow {
+    /// Lists all files and directories that a run of the workflow with input `values` reads.
+    ///
+    /// Walks values of the workflow inputs (or their defaults), defaults of the step inputs and
+    /// defaults of the run process inputs that the steps don't connect, including steps of
+    /// nested workflows, `secondaryFiles` and directory `listing`. Locations are deduplicated,
+    /// annotated with the consuming steps and sorted, steps of nested workflows are qualified
+    /// by the outer step id, e.g. `align/index`.
+    ///
+    /// ```
+    /// use zefiro_cwl::{CwlSchema, CwlValues};
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/wf-staging-schema.yml").unwrap();
+    /// let values = CwlValues::from_path("test_data/cwl/wf-staging-values.yml").unwrap();
+    /// let CwlSchema::Workflow(workflow) = schema else {
+    ///     panic!("Expected Workflow");
+    /// };
+    /// let files = workflow.input_files(&values).unwrap();
+    /// assert_eq!(files[0].location, "ftp://host/qc.cfg");
+    /// assert!(files[0].unsupported_scheme);
+    /// ```
+    pub fn input_files(&self, values: &CwlValues) -> Result<Vec<StagedFile>, CwlError> {
+        CwlError::check(self.validate_ids())?;
+        let mut files = BTreeMap::new();
+
+        for (input_id, input) in self.inputs() {
+            let value = match values.get(input_id) {
+                Some(value) => Some(value.clone()),
+                None => input
+                    .default
+                    .as_ref()
+                    .map(|default| default_value(input_id, default))
+                    .transpose()?,
+            };
+            let steps: Vec<&str> = self
+                .steps()
+                .filter(|(_, step)| {
+                    step.r#in.iter().any(|step_input| match &step_input.source {
+                        Some(Source::SingleSource(source)) => {
+                            source.trim_start_matches('#') == input_id
+                        }
+                        Some(Source::MultiSources(sources)) => sources
+                            .iter()
+                            .any(|source| source.trim_start_matches('#') == input_id),
+                        None => false,
+                    })
+                })
+                .map(|(step_id, _)| step_id)
+                .collect();
+            if let Some(value) = value {
+                collect_value(&mut files, &value, &steps);
+            }
+        }
+
+        self.collect_step_defaults(&mut files, "")?;
+
+        Ok(files
+            .into_values()
+            .map(|(mut file, steps): (StagedFile, BTreeSet<String>)| {
+                file.steps = steps.into_iter().collect();
+                file
+            })
+            .collect())
+    }
+
+    /// Collects defaults of the step inputs and of the run process inputs that the steps
+    /// don't connect, step ids are prefixed with `prefix`.
+    fn collect_step_defaults(
+        &self,
+        files: &mut BTreeMap<String, (StagedFile, BTreeSet<String>)>,
+        prefix: &str,
+    ) -> Result<(), CwlError> {
+        for (step_id, step) in self.steps() {
+            let step_id = format!("{prefix}{step_id}");
+            for step_input in &step.r#in {
+                if let Some(default) = &step_input.default {
+                    let id = format!("{step_id}/{}", step_input.id);
+                    collect_value(files, &default_value(&id, default)?, &[&step_id]);
+                }
+            }
+
+            let run_defaults: Vec<(&str, &Any)> = match &step.run {
+                StepRun::CommandLineTool(tool) => tool
+                    .inputs()
+                    .filter_map(|(id, input)| Some((id, input.default.as_ref()?)))
+                    .collect(),
+                StepRun::Workflow(workflow) => workflow
+                    .inputs()
+                    .filter_map(|(id, input)| Some((id, input.default.as_ref()?)))
+                    .collect(),
+                StepRun::Operation(_) | StepRun::Path(_) => vec![],
+            };
+            for (input_id, default) in run_defaults {
+                let connected = step.r#in.iter().any(|step_input| {
+                    step_input.id == input_id
+                        && (step_input.source.is_some() || step_input.default.is_some())
+                });
+                if !connected {
+                    let id = format!("{step_id}/{input_id}");
+                    collect_value(files, &default_value(&id, default)?, &[&step_id]);
+                }
+            }
+
+            if let StepRun::Workflow(workflow) = &step.run {
+                workflow.collect_step_defaults(files, &format!("{step_id}/"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_value(id: &str, default: &Any) -> Result<CwlValueType, CwlError> {
+    let Any::Any(value) = default;
+    serde_yaml::from_value(value.clone()).map_err(|e| {
+        CwlError::yaml_with_context(format!("Failed to deserialize default of input '{id}'"), e)
+    })
+}
+
+fn collect_value(
+    files: &mut BTreeMap<String, (StagedFile, BTreeSet<String>)>,
+    value: &CwlValueType,
+    steps: &[&str],
+) {
+    match value {
+        CwlValueType::Path(path) => collect_path(files, path, steps),
+        CwlValueType::Array(items) => {
+            for item in items {
+                collect_value(files, item, steps);
+            }
+        }
+        CwlValueType::Record(fields) => {
+            for field in fields.values() {
+                collect_value(files, field, steps);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_path(
+    files: &mut BTreeMap<String, (StagedFile, BTreeSet<String>)>,
+    path: &CwlPath,
+    steps: &[&str],
+) {
+    let (location, class, nested) = match path {
+        CwlPath::File(file) => (&file.location, StagedClass::File, &file.secondary_files),
+        CwlPath::Directory(dir) => (&dir.location, StagedClass::Directory, &dir.listing),
+    };
+    let (staged, consumers) = files
+        .entry(location.clone())
+        .or_insert_with(|| (StagedFile::new(location, class), BTreeSet::new()));
+    if let CwlPath::File(file) = path {
+        staged.size = staged.size.or(file.size);
+        staged.checksum = staged.checksum.take().or_else(|| file.checksum.clone());
+    }
+    consumers.extend(steps.iter().map(ToString::to_string));
+
+    for path in nested {
+        collect_path(files, path, steps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::document::CwlSchema;
+    use rstest::rstest;
+
+    /// Returns the staging workflow, its sources are prefixed with `#` if `fragment_sources`.
+    fn workflow(fragment_sources: bool) -> Workflow {
+        let mut yaml = std::fs::read_to_string("test_data/cwl/wf-staging-schema.yml").unwrap();
+        if fragment_sources {
+            for source in ["reference", "reads", "annotations", "align/bam"] {
+                yaml = yaml.replace(
+                    &format!("source: {source}\n"),
+                    &format!("source: '#{source}'\n"),
+                );
+            }
+        }
+        match yaml.parse().unwrap() {
+            CwlSchema::Workflow(workflow) => workflow,
+            _ => panic!("Expected Workflow"),
+        }
+    }
+
+    #[rstest]
+    fn test_input_files(#[values(false, true)] fragment_sources: bool) {
+        let values = CwlValues::from_path("test_data/cwl/wf-staging-values.yml").unwrap();
+        let files = workflow(fragment_sources).input_files(&values).unwrap();
+
+        let summary: Vec<(&str, StagedClass, Vec<&str>)> = files
+            .iter()
+            .map(|f| {
+                let steps = f.steps.iter().map(String::as_str).collect();
+                (f.location.as_str(), f.class, steps)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ftp://host/qc.cfg", StagedClass::File, vec!["qc"]),
+                (
+                    "gs://bucket/annotations",
+                    StagedClass::Directory,
+                    vec!["qc"]
+                ),
+                (
+                    "gs://bucket/annotations/genes.gtf",
+                    StagedClass::File,
+                    vec!["qc"]
+                ),
+                ("s3://bucket/reads_1.fq", StagedClass::File, vec!["align"]),
+                ("s3://bucket/reads_2.fq", StagedClass::File, vec!["align"]),
+                (
+                    "s3://bucket/reference.fa",
+                    StagedClass::File,
+                    vec!["align", "qc"]
+                ),
+                (
+                    "s3://bucket/reference.fa.fai",
+                    StagedClass::File,
+                    vec!["align", "qc"]
+                ),
+            ]
+        );
+        assert_eq!(
+            files
+                .iter()
+                .map(|f| f.unsupported_scheme)
+                .collect::<Vec<_>>(),
+            vec![true, false, false, false, false, false, false]
+        );
+        assert_eq!(files[5].size, Some(1024));
+        assert!(files[4].checksum.is_some());
+    }
+
+    #[test]
+    fn test_input_files_run_defaults() {
+        let yaml = "cwlVersion: v1.2\nclass: Workflow\nrequirements: []\n\
+                    inputs:\n  reads: File\noutputs: []\n\
+                    steps:\n  align:\n    in: {reads: reads}\n    out: []\n    run:\n\
+                    \x20     class: Workflow\n      requirements: []\n      outputs: []\n\
+                    \x20     inputs:\n        reads: File\n\
+                    \x20       reference: {type: File, default: {class: File, location: s3://bucket/ref.fa}}\n\
+                    \x20     steps:\n        index:\n          in: {reference: reference}\n          out: []\n\
+                    \x20         run:\n            class: CommandLineTool\n            outputs: []\n\
+                    \x20           inputs:\n              reference: File\n\
+                    \x20             config: {type: File, default: {class: File, location: s3://bucket/index.cfg}}\n";
+        let CwlSchema::Workflow(workflow) = yaml.parse().unwrap() else {
+            panic!("Expected Workflow");
+        };
+        let values =
+            CwlValues::from_string("reads: {class: File, location: s3://bucket/reads.fq}").unwrap();
+        let files = workflow.input_files(&values).unwrap();
+        assert_eq!(
+            files
+                .iter()
+                .map(|f| (f.location.as_str(), f.steps.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("s3://bucket/index.cfg", vec!["align/index".to_string()]),
+                ("s3://bucket/reads.fq", vec!["align".to_string()]),
+                ("s3://bucket/ref.fa", vec!["align".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_files_json() {
+        let values = CwlValues::from_path("test_data/cwl/wf-staging-values.yml").unwrap();
+        let files = workflow(false).input_files(&values).unwrap();
+        let json = serde_json::to_value(&files[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "location": "ftp://host/qc.cfg",
+                "class": "File",
+                "steps": ["qc"],
+                "size": null,
+                "checksum": null,
+                "unsupportedScheme": true,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case("/data/file.txt", false)]
+    #[case("file:///data/file.txt", false)]
+    #[case("https://host/file.txt", false)]
+    #[case("ftp://host/file.txt", true)]
+    fn test_staged_file_scheme(#[case] location: &str, #[case] unsupported: bool) {
+        assert_eq!(
+            StagedFile::new(location, StagedClass::File).unsupported_scheme,
+            unsupported
+        );
+    }
+}
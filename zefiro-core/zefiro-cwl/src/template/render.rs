@@ -1,4 +1,4 @@
-use anyhow::Error;
+use crate::error::{CwlError, TemplateError};
 use serde_json::Value;
 use tera::{Context, Tera};
 
@@ -8,21 +8,26 @@ pub struct TemplateRender {
 }
 
 impl TemplateRender {
-    pub fn new(content: Value, template: &str) -> Result<Self, Error> {
+    pub fn new(content: Value, template: &str) -> Result<Self, CwlError> {
         let mut tera = Tera::default();
-        tera.add_raw_template("template", template)?;
+        tera.add_raw_template("template", template)
+            .map_err(|e| TemplateError::Parse(e.into()))?;
         Ok(Self { content, tera })
     }
 
-    pub fn render(&self) -> Result<String, Error> {
+    pub fn render(&self) -> Result<String, CwlError> {
         let mut context = Context::new();
-        let object = self.content.as_object().ok_or_else(|| {
-            anyhow::anyhow!("Content must be a JSON object, got: {}", self.content)
-        })?;
+        let object = self
+            .content
+            .as_object()
+            .ok_or_else(|| TemplateError::Content(self.content.clone()))?;
         for (key, value) in object {
             context.insert(key, value);
         }
-        let result = self.tera.render("template", &context)?;
+        let result = self
+            .tera
+            .render("template", &context)
+            .map_err(|e| TemplateError::Render(e.into()))?;
 
         Ok(result)
     }
@@ -79,4 +84,28 @@ mod tests {
         let rendered = template_render.render().unwrap();
         assert_eq!(rendered, expected);
     }
+
+    #[test]
+    fn test_render_errors() {
+        let result = TemplateRender::new(json!({}), "{{ unclosed");
+        assert!(matches!(
+            result,
+            Err(CwlError::Template(TemplateError::Parse(_)))
+        ));
+
+        let error = TemplateRender::new(json!([1]), "text")
+            .unwrap()
+            .render()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Content must be a JSON object, got: [1]");
+
+        let error = TemplateRender::new(json!({}), "{{ missing }}")
+            .unwrap()
+            .render()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CwlError::Template(TemplateError::Render(_))
+        ));
+    }
 }
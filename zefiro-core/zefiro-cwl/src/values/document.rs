@@ -1,5 +1,5 @@
+use crate::error::CwlError;
 use crate::values::types::CwlValueType;
-use anyhow::{Error, Result};
 use serde::{Deserialize, Serialize};
 use serde_yaml;
 use std::{
@@ -32,17 +32,17 @@ impl CwlValues {
     /// let yaml_file = "test_data/cwl/clt-step-values.yml";
     /// let values = CwlValues::from_path(yaml_file).expect("Failed to deserialize CWL values document");
     /// ```
-    pub fn from_path(path: &str) -> Result<Self, Error> {
-        let reader = BufReader::new(
-            File::open(path)
-                .map_err(|e| Error::msg(format!("Failed to open file '{}': {}", path, e)))?,
-        );
+    pub fn from_path(path: &str) -> Result<Self, CwlError> {
+        let reader = BufReader::new(File::open(path).map_err(|source| CwlError::Io {
+            path: path.to_string(),
+            source,
+        })?);
 
         serde_yaml::from_reader(reader).map_err(|e| {
-            Error::msg(format!(
-                "Failed to deserialize CWL values from '{}'; {}",
-                path, e
-            ))
+            CwlError::yaml_with_context(
+                format!("Failed to deserialize CWL values from '{path}'"),
+                e,
+            )
         })
     }
 
@@ -60,19 +60,18 @@ impl CwlValues {
     ///
     /// let values = CwlValues::from_string(yaml_input).expect("Failed to deserialize CWL values document");
     /// ```
-    pub fn from_string(yaml_input: &str) -> Result<Self, Error> {
+    pub fn from_string(yaml_input: &str) -> Result<Self, CwlError> {
         serde_yaml::from_str(yaml_input).map_err(|e| {
-            Error::msg(format!(
-                "Failed to deserialize CWL values from string: {}",
-                e
-            ))
+            CwlError::yaml_with_context("Failed to deserialize CWL values from string", e)
         })
     }
 
-    /// Deserializes CwlValues structure into `string`.
-    pub fn to_string(&self) -> Result<String, Error> {
-        serde_yaml::to_string(self)
-            .map_err(|e| Error::msg(format!("Failed to dserialize CWL values to string: {}", e)))
+    /// Serializes CwlValues structure into `string`.
+    pub fn to_string(&self) -> Result<String, CwlError> {
+        serde_yaml::to_string(self).map_err(|source| CwlError::Serialization {
+            document: "CWL values",
+            source,
+        })
     }
 
     /// Serializes CwlValues structure and writes it into `file`.
@@ -93,8 +92,11 @@ impl CwlValues {
     /// let mut writer = BufWriter::new(tmpfile);
     /// values.to_yaml(writer);
     /// ```
-    pub fn to_yaml<W: Write>(&self, writer: W) -> Result<()> {
-        serde_yaml::to_writer(writer, self).map_err(Into::into)
+    pub fn to_yaml<W: Write>(&self, writer: W) -> Result<(), CwlError> {
+        serde_yaml::to_writer(writer, self).map_err(|source| CwlError::Serialization {
+            document: "CWL values",
+            source,
+        })
     }
 }
 
@@ -128,4 +130,39 @@ mod tests {
             serde_yaml::to_value(&written_values).unwrap()
         );
     }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_cwlvaluetype_roundtrip() {
+        crate::arbitrary_helpers::check_arbitrary(512, |value: CwlValueType| {
+            let yaml = serde_yaml::to_string(&value).unwrap();
+            let parsed: CwlValueType = serde_yaml::from_str(&yaml).unwrap();
+            let written = serde_yaml::to_string(&parsed).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<serde_yaml::Value>(&yaml).unwrap(),
+                serde_yaml::from_str::<serde_yaml::Value>(&written).unwrap(),
+                "{yaml}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_cwlvalues_error_messages() {
+        let error = CwlValues::from_path("test_data/cwl/missing.yml").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Failed to open file 'test_data/cwl/missing.yml': "));
+
+        let error = CwlValues::from_string("- in_file").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to deserialize CWL values from string: invalid type: sequence, expected struct CwlValues"
+        );
+
+        let error = CwlValues::from_string("in_file: {class: File}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to deserialize CWL values from string: missing field `location`"
+        );
+    }
 }
@@ -1,15 +1,22 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_yaml::Value;
 use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
 use std::path::Path;
 
 /// Represents a `File` object in CWL
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CwlFile {
     /// Full path to the file, e.g., "/path/to/file.txt".
     pub location: String,
 
+    /// Local path to the file once it is staged for a tool, e.g., "/tmp/inputs/file.txt".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
     /// Basename of the file, e.g., "file.txt".
     #[serde(default)]
     pub basename: Option<String>,
@@ -29,6 +36,19 @@ pub struct CwlFile {
     /// SHA-1 checksum of the file, e.g., "c63b83369243849f80049b2726dcc8db0b18d03e".
     #[serde(default)]
     pub checksum: Option<String>,
+
+    /// Identifier of the file format, e.g., "edam:format_1930".
+    #[serde(default)]
+    pub format: Option<String>,
+
+    /// Files and directories that accompany the file, e.g., an index of "reference.fa".
+    #[serde(
+        default,
+        rename = "secondaryFiles",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    pub secondary_files: Vec<CwlPath>,
 }
 
 impl CwlFile {
@@ -82,8 +102,18 @@ impl CwlFile {
 
 /// Represents a `Directory` object in CWL
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CwlDirectory {
     pub location: String,
+
+    /// Local path to the directory once it is staged for a tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Files and directories contained in the directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    pub listing: Vec<CwlPath>,
 }
 
 impl CwlDirectory {
@@ -93,15 +123,28 @@ impl CwlDirectory {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "class", rename_all = "PascalCase")]
 pub enum CwlPath {
     File(CwlFile),
     Directory(CwlDirectory),
 }
 
+impl CwlPath {
+    /// Returns the local path bound on the command line, the location if the path is
+    /// not set yet.
+    pub fn path(&self) -> &str {
+        let (path, location) = match self {
+            Self::File(file) => (&file.path, &file.location),
+            Self::Directory(directory) => (&directory.path, &directory.location),
+        };
+        path.as_deref().unwrap_or(location)
+    }
+}
+
 /// CWL value types with tagged enum for `File` and `Directory`
 #[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(untagged)]
+#[serde(untagged, remote = "Self")]
 pub enum CwlValueType {
     Boolean(bool),
     Int(i32),
@@ -111,4 +154,63 @@ pub enum CwlValueType {
     String(String),
     Path(CwlPath),
     Array(Vec<Self>),
+    /// Value of a `record` type, mappings with `class: File` or `class: Directory` are parsed
+    /// as `Path` instead.
+    Record(BTreeMap<String, Self>),
+}
+
+impl Serialize for CwlValueType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CwlValueType {
+    /// Mappings with `class: File` or `class: Directory` are parsed as `Path` only, so an
+    /// invalid `File` is reported with its own error instead of being taken for a record.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match value.get("class").and_then(Value::as_str) {
+            Some("File" | "Directory") => serde_yaml::from_value(value).map(Self::Path),
+            _ => Self::deserialize(value),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CwlValueType {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Self::arbitrary_with_depth(u, crate::arbitrary_helpers::MAX_DEPTH)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl CwlValueType {
+    fn arbitrary_with_depth(
+        u: &mut arbitrary::Unstructured<'_>,
+        depth: usize,
+    ) -> arbitrary::Result<Self> {
+        // Numbers are parsed as `Float` first, so `Double`s are not generated: they don't
+        // survive a roundtrip
+        let variants = if depth == 0 { 6 } else { 8 };
+        Ok(match u.choose_index(variants)? {
+            0 => Self::Boolean(u.arbitrary()?),
+            1 => Self::Int(u.arbitrary()?),
+            2 => Self::Long(u.arbitrary()?),
+            3 => Self::Float(u.arbitrary()?),
+            4 => Self::String(u.arbitrary()?),
+            5 => Self::Path(u.arbitrary()?),
+            6 => Self::Array(crate::arbitrary_helpers::arbitrary_vec(u, |u| {
+                Self::arbitrary_with_depth(u, depth - 1)
+            })?),
+            _ => Self::Record(
+                crate::arbitrary_helpers::arbitrary_vec(u, |u| {
+                    Ok((u.arbitrary()?, Self::arbitrary_with_depth(u, depth - 1)?))
+                })?
+                .into_iter()
+                .collect(),
+            ),
+        })
+    }
 }
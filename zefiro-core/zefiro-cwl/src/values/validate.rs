@@ -0,0 +1,447 @@
+use crate::error::{Severity, ValidationError};
+use crate::schema::document::CwlSchema;
+use crate::schema::types::{is_enum_symbol, Any, CwlSchemaType, Format, Namespaces, RecordField};
+use crate::values::document::CwlValues;
+use crate::values::types::{CwlPath, CwlValueType};
+use std::fmt;
+
+/// Input parameter of a process: id, type, accepted formats and default value.
+type Input<'a> = (
+    &'a str,
+    &'a CwlSchemaType,
+    Option<&'a Format>,
+    Option<&'a Any>,
+);
+
+fn inputs(schema: &CwlSchema) -> Vec<Input<'_>> {
+    match schema {
+        CwlSchema::CommandLineTool(tool) => tool
+            .inputs()
+            .map(|(id, i)| (id, &i.r#type, i.format.as_ref(), i.default.as_ref()))
+            .collect(),
+        CwlSchema::Workflow(workflow) => workflow
+            .inputs()
+            .map(|(id, i)| (id, &i.r#type, i.format.as_ref(), i.default.as_ref()))
+            .collect(),
+        CwlSchema::Operation(operation) => operation
+            .inputs()
+            .map(|(id, i)| (id, &i.r#type, i.format.as_ref(), i.default.as_ref()))
+            .collect(),
+    }
+}
+
+impl CwlValues {
+    /// Checks values against input parameters of the `schema`: types of the values, including
+    /// fields of records and symbols of enums, and `format` of `File` values.
+    ///
+    /// ```
+    /// use zefiro_cwl::{CwlSchema, CwlValues};
+    ///
+    /// let schema = CwlSchema::from_path("test_data/cwl/clt-step-schema.yml").unwrap();
+    /// let values = CwlValues::from_path("test_data/cwl/clt-step-values.yml").unwrap();
+    /// assert!(values.validate(&schema).is_ok());
+    /// ```
+    pub fn validate(&self, schema: &CwlSchema) -> Result<(), Vec<ValidationError>> {
+        let checker = TypeChecker { schema };
+        let namespaces = schema.namespaces();
+        let mut errors = vec![];
+        for (id, r#type, format, _) in inputs(schema) {
+            let Some(value) = self.get(id) else {
+                continue;
+            };
+            errors.extend(checker.errors(id, value, r#type));
+            if let Some(format) = format {
+                format_errors(id.to_string(), value, format, &namespaces, &mut errors);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl CwlSchema {
+    /// Checks that `default` values of the inputs match their types, e.g. that a default
+    /// of an enum input is one of the symbols.
+    pub fn validate_defaults(&self) -> Vec<ValidationError> {
+        let checker = TypeChecker { schema: self };
+        let mut errors = vec![];
+        for (position, (_, r#type, _, default)) in inputs(self).into_iter().enumerate() {
+            let path = format!("inputs[{position}].default");
+            // `default: null` is the same as no default
+            let Some(Any::Any(default)) = default.filter(|Any::Any(d)| !d.is_null()) else {
+                continue;
+            };
+            match serde_yaml::from_value::<CwlValueType>(default.clone()) {
+                Ok(value) => errors.extend(checker.errors(&path, &value, r#type)),
+                Err(e) => errors.push(ValidationError {
+                    severity: Severity::Error,
+                    path,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        errors
+    }
+}
+
+/// Checks values against types, named types are resolved in `SchemaDefRequirement`s of the
+/// `schema`, unknown named types accept any value.
+struct TypeChecker<'a> {
+    schema: &'a CwlSchema,
+}
+
+impl TypeChecker<'_> {
+    fn errors(
+        &self,
+        path: &str,
+        value: &CwlValueType,
+        r#type: &CwlSchemaType,
+    ) -> Vec<ValidationError> {
+        match r#type {
+            CwlSchemaType::Any(name) => self.named_errors(path, value, name),
+            CwlSchemaType::Array(types) => {
+                if types.iter().any(|t| self.errors(path, value, t).is_empty()) {
+                    return vec![];
+                }
+                // Report the nested errors if the union is just an optional type
+                match types.iter().filter(|t| !t.is_null()).collect::<Vec<_>>()[..] {
+                    [single] => self.errors(path, value, single),
+                    _ => vec![mismatch(path, r#type, value)],
+                }
+            }
+            CwlSchemaType::Record(record) => {
+                self.record_errors(path, value, record.fields.fields())
+            }
+            CwlSchemaType::Enum(r#enum) => enum_errors(path, value, &r#enum.symbols),
+            CwlSchemaType::Map(_) => match r#type.item_type() {
+                Some(items) => self.array_errors(path, value, items),
+                None => vec![],
+            },
+        }
+    }
+
+    fn named_errors(&self, path: &str, value: &CwlValueType, name: &str) -> Vec<ValidationError> {
+        let matches = match name {
+            "Any" => true,
+            "null" => false,
+            "boolean" => matches!(value, CwlValueType::Boolean(_)),
+            "int" => matches!(value, CwlValueType::Int(_)),
+            "long" => matches!(value, CwlValueType::Int(_) | CwlValueType::Long(_)),
+            "float" | "double" => matches!(
+                value,
+                CwlValueType::Int(_)
+                    | CwlValueType::Long(_)
+                    | CwlValueType::Float(_)
+                    | CwlValueType::Double(_)
+            ),
+            "string" => matches!(value, CwlValueType::String(_)),
+            "File" => matches!(value, CwlValueType::Path(CwlPath::File(_))),
+            "Directory" => matches!(value, CwlValueType::Path(CwlPath::Directory(_))),
+            _ => {
+                return match self.schema.resolve_type(name) {
+                    Some(def) => self.errors(path, value, &def.r#type),
+                    None => vec![],
+                }
+            }
+        };
+        if matches {
+            vec![]
+        } else {
+            vec![mismatch(path, name, value)]
+        }
+    }
+
+    fn array_errors(
+        &self,
+        path: &str,
+        value: &CwlValueType,
+        items: &CwlSchemaType,
+    ) -> Vec<ValidationError> {
+        let CwlValueType::Array(values) = value else {
+            return vec![mismatch(path, format!("{items}[]"), value)];
+        };
+        values
+            .iter()
+            .enumerate()
+            .flat_map(|(position, item)| self.errors(&format!("{path}[{position}]"), item, items))
+            .collect()
+    }
+
+    fn record_errors(
+        &self,
+        path: &str,
+        value: &CwlValueType,
+        fields: &[RecordField],
+    ) -> Vec<ValidationError> {
+        let CwlValueType::Record(values) = value else {
+            return vec![mismatch(path, "record", value)];
+        };
+        let mut errors = vec![];
+        for field in fields {
+            let field_path = format!("{path}.{}", field.name);
+            match values.get(&field.name) {
+                Some(value) => errors.extend(self.errors(&field_path, value, &field.r#type)),
+                None if !field.r#type.is_optional() => errors.push(ValidationError {
+                    severity: Severity::Error,
+                    path: field_path,
+                    message: "Missing value for required field".to_string(),
+                }),
+                None => {}
+            }
+        }
+        errors
+    }
+}
+
+fn enum_errors(path: &str, value: &CwlValueType, symbols: &[String]) -> Vec<ValidationError> {
+    let CwlValueType::String(symbol) = value else {
+        return vec![mismatch(path, "enum", value)];
+    };
+    if is_enum_symbol(symbols, symbol) {
+        return vec![];
+    }
+    vec![ValidationError {
+        severity: Severity::Error,
+        path: path.to_string(),
+        message: format!(
+            "Value '{symbol}' is not one of allowed symbols: {}",
+            symbols.join(", ")
+        ),
+    }]
+}
+
+fn mismatch(path: &str, expected: impl fmt::Display, value: &CwlValueType) -> ValidationError {
+    let actual = match value {
+        CwlValueType::Boolean(_) => "boolean",
+        CwlValueType::Int(_) => "int",
+        CwlValueType::Long(_) => "long",
+        CwlValueType::Double(_) => "double",
+        CwlValueType::Float(_) => "float",
+        CwlValueType::String(_) => "string",
+        CwlValueType::Path(CwlPath::File(_)) => "File",
+        CwlValueType::Path(CwlPath::Directory(_)) => "Directory",
+        CwlValueType::Array(_) => "array",
+        CwlValueType::Record(_) => "record",
+    };
+    ValidationError {
+        severity: Severity::Error,
+        path: path.to_string(),
+        message: format!("Expected {expected}, got {actual}"),
+    }
+}
+
+fn format_errors(
+    path: String,
+    value: &CwlValueType,
+    format: &Format,
+    namespaces: &Namespaces,
+    errors: &mut Vec<ValidationError>,
+) {
+    match value {
+        CwlValueType::Path(CwlPath::File(file)) => match &file.format {
+            Some(file_format) if !format.accepts(file_format, namespaces) => {
+                errors.push(ValidationError {
+                    severity: Severity::Error,
+                    path,
+                    message: format!(
+                        "File format '{file_format}' is not one of accepted formats: {}",
+                        format.formats().join(", ")
+                    ),
+                })
+            }
+            _ => {}
+        },
+        CwlValueType::Array(items) => {
+            for (position, item) in items.iter().enumerate() {
+                format_errors(
+                    format!("{path}[{position}]"),
+                    item,
+                    format,
+                    namespaces,
+                    errors,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const SCHEMA: &str = r#"
+cwlVersion: v1.2
+class: CommandLineTool
+$namespaces:
+  edam: http://edamontology.org/
+inputs:
+  - id: reference
+    type: File
+    format: edam:format_1929
+  - id: reads
+    type:
+      type: array
+      items: File
+    format: [edam:format_1930, edam:format_1931]
+outputs: []
+"#;
+
+    #[rstest]
+    #[case("reference: {class: File, location: ref.fa}", vec![])]
+    #[case("reference: {class: File, location: ref.fa, format: edam:format_1929}", vec![])]
+    #[case(
+        "reads: [{class: File, location: a.fq, format: edam:format_1931}]",
+        vec![]
+    )]
+    #[case(
+        "reference: {class: File, location: ref.fa, format: 'http://edamontology.org/format_1929'}",
+        vec![]
+    )]
+    #[case(
+        "reference: {class: File, location: ref.fa, format: 'http://edamontology.org/format_1930'}",
+        vec!["reference: File format 'http://edamontology.org/format_1930' is not one of accepted formats: edam:format_1929"]
+    )]
+    #[case(
+        "reference: {class: File, location: ref.fa, format: edam:format_1930}",
+        vec!["reference: File format 'edam:format_1930' is not one of accepted formats: edam:format_1929"]
+    )]
+    #[case(
+        "reads: [{class: File, location: a.fq}, {class: File, location: b.bam, format: edam:format_2572}]",
+        vec!["reads[1]: File format 'edam:format_2572' is not one of accepted formats: edam:format_1930, edam:format_1931"]
+    )]
+    fn test_validate_file_format(#[case] values: &str, #[case] expected: Vec<&str>) {
+        let schema: CwlSchema = SCHEMA.parse().unwrap();
+        let values = CwlValues::from_string(values).unwrap();
+        let errors = values.validate(&schema).err().unwrap_or_default();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    const RECORD_SCHEMA: &str = r##"
+cwlVersion: v1.2
+class: CommandLineTool
+inputs:
+  - id: sample
+    type:
+      type: record
+      fields:
+        name: string
+        reads:
+          type: File
+        tags: string[]
+        paired: boolean?
+  - id: samples
+    type:
+      type: array
+      items: "#sample"
+outputs: []
+requirements:
+  - class: SchemaDefRequirement
+    types:
+      - name: "#sample"
+        type: record
+        fields:
+          - name: name
+            type: string
+          - name: lane
+            type: int
+"##;
+
+    #[rstest]
+    #[case("sample: {name: s1, reads: {class: File, location: s1.fq}, tags: []}", vec![])]
+    #[case("samples: [{name: s1, lane: 1}, {name: s2, lane: 2}]", vec![])]
+    #[case(
+        "sample: {name: s1, tags: [a, 1]}",
+        vec!["sample.reads: Missing value for required field", "sample.tags[1]: Expected string, got int"]
+    )]
+    #[case(
+        "sample: {name: s1, reads: {class: File, location: s1.fq}, tags: [], paired: yes}",
+        vec!["sample.paired: Expected boolean, got string"]
+    )]
+    #[case("sample: s1", vec!["sample: Expected record, got string"])]
+    #[case(
+        "samples: [{name: s1, lane: one}, {lane: 2}]",
+        vec!["samples[0].lane: Expected int, got string", "samples[1].name: Missing value for required field"]
+    )]
+    fn test_validate_record(#[case] values: &str, #[case] expected: Vec<&str>) {
+        let schema: CwlSchema = RECORD_SCHEMA.parse().unwrap();
+        let values = CwlValues::from_string(values).unwrap();
+        let errors = values.validate(&schema).err().unwrap_or_default();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    const ENUM_SCHEMA: &str = r##"
+cwlVersion: v1.2
+class: CommandLineTool
+inputs:
+  - id: order
+    type:
+      type: enum
+      symbols: [sorted, unsorted]
+    default: sorted
+  - id: mode
+    type: ["null", "#mode"]
+    default: fast
+outputs: []
+requirements:
+  - class: SchemaDefRequirement
+    types:
+      - name: "#mode"
+        type: enum
+        symbols: ["#mode/fast", "#mode/accurate"]
+"##;
+
+    #[rstest]
+    #[case("order: unsorted\nmode: accurate", vec![])]
+    #[case(
+        "order: random",
+        vec!["order: Value 'random' is not one of allowed symbols: sorted, unsorted"]
+    )]
+    #[case("order: 1", vec!["order: Expected enum, got int"])]
+    #[case(
+        "mode: slow",
+        vec!["mode: Value 'slow' is not one of allowed symbols: #mode/fast, #mode/accurate"]
+    )]
+    fn test_validate_enum(#[case] values: &str, #[case] expected: Vec<&str>) {
+        let schema: CwlSchema = ENUM_SCHEMA.parse().unwrap();
+        let values = CwlValues::from_string(values).unwrap();
+        let errors = values.validate(&schema).err().unwrap_or_default();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_validate_defaults() {
+        let schema: CwlSchema = ENUM_SCHEMA.parse().unwrap();
+        assert!(schema.validate_defaults().is_empty());
+
+        let schema: CwlSchema = ENUM_SCHEMA
+            .replace("default: sorted", "default: shuffled")
+            .replace("default: fast", "default: slow")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            schema
+                .validate_defaults()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "inputs[0].default: Value 'shuffled' is not one of allowed symbols: sorted, unsorted",
+                "inputs[1].default: Value 'slow' is not one of allowed symbols: #mode/fast, #mode/accurate"
+            ]
+        );
+    }
+}